@@ -0,0 +1,5 @@
+fn main() {
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/catboost.proto")
+        .expect("failed to compile the gRPC service");
+}
@@ -0,0 +1,87 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Field, Fields};
+
+enum FeatureKind {
+    Float,
+    Cat,
+}
+
+/// Derive `catboost_rs::ScoreRecord` for a struct with named fields. Fields marked
+/// `#[catboost(float)]` become float features (converted with `as f32`) and fields marked
+/// `#[catboost(cat)]` become categorical features (converted with `ToString`), both in
+/// declaration order. Unmarked fields are ignored.
+#[proc_macro_derive(ScoreRecord, attributes(catboost))]
+pub fn derive_score_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "ScoreRecord can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "ScoreRecord can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut float_fields = Vec::new();
+    let mut cat_fields = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        match feature_kind(field)? {
+            Some(FeatureKind::Float) => float_fields.push(ident),
+            Some(FeatureKind::Cat) => cat_fields.push(ident),
+            None => {}
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::catboost_rs::ScoreRecord for #name #ty_generics #where_clause {
+            fn float_features(&self) -> ::std::vec::Vec<f32> {
+                ::std::vec![#(self.#float_fields as f32),*]
+            }
+
+            fn cat_features(&self) -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(::std::string::ToString::to_string(&self.#cat_fields)),*]
+            }
+        }
+    })
+}
+
+fn feature_kind(field: &Field) -> syn::Result<Option<FeatureKind>> {
+    let mut kind = None;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("catboost") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("float") {
+                kind = Some(FeatureKind::Float);
+                Ok(())
+            } else if meta.path.is_ident("cat") {
+                kind = Some(FeatureKind::Cat);
+                Ok(())
+            } else {
+                Err(meta.error("expected `float` or `cat`"))
+            }
+        })?;
+    }
+    Ok(kind)
+}
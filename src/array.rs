@@ -0,0 +1,50 @@
+use crate::error::CatBoostResult;
+use crate::model::Model;
+use ndarray::ArrayView2;
+
+impl Model {
+    /// Calculate raw model predictions on an `ndarray` float feature view and string
+    /// categorical feature values. Row-major views are scored without copying.
+    pub fn predict_array<C, S>(
+        &self,
+        float_features: ArrayView2<f32>,
+        cat_features: &[C],
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let float_features = float_features.as_standard_layout();
+        let shape = float_features.dim();
+        let float_features = float_features
+            .as_slice()
+            .expect("standard layout arrays are contiguous");
+        self.calc_model_prediction_matrix(float_features, shape, cat_features)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr2;
+
+    #[test]
+    fn predict_array() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = arr2(&[[-10.0, 5.0, 753.0], [30.0, 1.0, 760.0], [40.0, 0.1, 705.0]]);
+        let cat_features = [["north"], ["south"], ["south"]];
+        let prediction = model
+            .predict_array(float_features.view(), &cat_features)
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+
+        // Same features stored column-major, so the view is not in standard layout
+        let columns = float_features.t().as_standard_layout().into_owned();
+        assert!(!columns.t().is_standard_layout());
+        let column_prediction = model.predict_array(columns.t(), &cat_features).unwrap();
+        assert_eq!(column_prediction, prediction);
+    }
+}
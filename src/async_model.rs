@@ -0,0 +1,48 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+use std::sync::Arc;
+
+impl Model {
+    /// Calculate raw model predictions on a tokio blocking thread, so large batches don't stall
+    /// the async runtime while the native evaluator runs
+    pub async fn predict_async(
+        self: Arc<Self>,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+    ) -> CatBoostResult<Vec<f64>> {
+        tokio::task::spawn_blocking(move || {
+            self.calc_model_prediction(float_features, cat_features)
+        })
+        .await
+        .map_err(|e| CatBoostError::TaskFailed(format!("prediction task failed: {}", e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn predict_async() {
+        let model = Arc::new(Model::load("files/model.bin").unwrap());
+        let prediction = model
+            .predict_async(
+                vec![
+                    vec![-10.0, 5.0, 753.0],
+                    vec![30.0, 1.0, 760.0],
+                    vec![40.0, 0.1, 705.0],
+                ],
+                vec![
+                    vec![String::from("north")],
+                    vec![String::from("south")],
+                    vec![String::from("south")],
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::feature::{FeatureKind, FeatureValue, Row};
+use crate::hash::{hash_cat_feature, hash_cat_features};
+use crate::model::{Model, PointerArray};
+use crate::prediction_type::PredictionType;
+
+impl Model {
+    /// Start building a batch of documents for this model, added one at a time
+    pub fn batch_builder(&self) -> PredictionBatchBuilder<'_> {
+        PredictionBatchBuilder {
+            model: Some(self),
+            ..PredictionBatchBuilder::new(
+                self.get_float_features_count(),
+                self.get_cat_features_count(),
+            )
+        }
+    }
+
+    /// Calculate raw model predictions on a batch built with `Model::batch_builder`. Results
+    /// hold as many values per document as the prediction type gives, see `PredictionType`.
+    pub fn predict_batch(&self, batch: &PredictionBatch) -> CatBoostResult<Vec<f64>> {
+        self.predict_batch_as(batch, self.get_prediction_type())
+    }
+
+    /// Calculate predictions of `prediction_type` on a batch, whatever prediction type is set on
+    /// the model
+    pub(crate) fn predict_batch_as(
+        &self,
+        batch: &PredictionBatch,
+        prediction_type: PredictionType,
+    ) -> CatBoostResult<Vec<f64>> {
+        if batch.float_features_count != self.get_float_features_count()
+            || batch.cat_features_count != self.get_cat_features_count()
+        {
+            return Err(CatBoostError::InvalidArgument(format!(
+                "batch has {} float and {} categorical features, model expects {} and {}",
+                batch.float_features_count,
+                batch.cat_features_count,
+                self.get_float_features_count(),
+                self.get_cat_features_count()
+            )));
+        }
+
+        let float_features_ptr = (0..batch.len())
+            .map(|doc| batch.float_features[doc * batch.float_features_count..].as_ptr())
+            .collect::<PointerArray<_>>();
+        let hashed_cat_features = (0..batch.len())
+            .map(|doc| {
+                &batch.hashed_cat_features
+                    [doc * batch.cat_features_count..(doc + 1) * batch.cat_features_count]
+            })
+            .collect::<Vec<_>>();
+        self.calc_hashed_prediction(
+            float_features_ptr,
+            batch.float_features_count,
+            &hashed_cat_features,
+            prediction_type,
+            self.prediction_dimensions(prediction_type),
+        )
+    }
+}
+
+/// Builder adding documents to a `PredictionBatch` one at a time, created by
+/// `Model::batch_builder`. Each document is checked against the model when it is added.
+#[derive(Debug)]
+pub struct PredictionBatchBuilder<'a> {
+    model: Option<&'a Model>,
+    batch: PredictionBatch,
+}
+
+impl PredictionBatchBuilder<'_> {
+    /// Start building a batch of documents with the given feature counts without a model at
+    /// hand, e.g. to feed a mock `Predictor`
+    pub fn new(float_features_count: usize, cat_features_count: usize) -> Self {
+        PredictionBatchBuilder {
+            model: None,
+            batch: PredictionBatch {
+                docs: 0,
+                float_features_count,
+                cat_features_count,
+                float_features: Vec::new(),
+                hashed_cat_features: Vec::new(),
+            },
+        }
+    }
+
+    /// Add a document given as float features and categorical feature values in model order
+    pub fn push<S: AsRef<str>>(
+        &mut self,
+        float_features: &[f32],
+        cat_features: &[S],
+    ) -> CatBoostResult<()> {
+        self.check_count(float_features.len(), FeatureKind::Float)?;
+        self.check_count(cat_features.len(), FeatureKind::Categorical)?;
+        self.batch.float_features.extend_from_slice(float_features);
+        self.batch
+            .hashed_cat_features
+            .extend(hash_cat_features(cat_features));
+        self.batch.docs += 1;
+        Ok(())
+    }
+
+    /// Add a document given as a row of float, missing and categorical values
+    pub fn push_row(&mut self, row: &Row) -> CatBoostResult<()> {
+        let mut float_features = Vec::with_capacity(self.batch.float_features_count);
+        let mut hashed_cat_features = Vec::with_capacity(self.batch.cat_features_count);
+        for value in row.values() {
+            match value {
+                FeatureValue::Float(value) => float_features.push(*value),
+                FeatureValue::Missing => float_features.push(f32::NAN),
+                FeatureValue::Cat(value) => hashed_cat_features.push(hash_cat_feature(value)),
+                FeatureValue::Text(_) | FeatureValue::Embedding(_) => {
+                    return Err(CatBoostError::InvalidArgument(format!(
+                        "document {}: batches do not hold text or embedding features",
+                        self.batch.len()
+                    )))
+                }
+            }
+        }
+        self.check_count(float_features.len(), FeatureKind::Float)?;
+        self.check_count(hashed_cat_features.len(), FeatureKind::Categorical)?;
+        self.batch.float_features.extend(float_features);
+        self.batch.hashed_cat_features.extend(hashed_cat_features);
+        self.batch.docs += 1;
+        Ok(())
+    }
+
+    /// Number of documents added so far
+    pub fn len(&self) -> usize {
+        self.batch.len()
+    }
+
+    /// Whether no documents have been added yet
+    pub fn is_empty(&self) -> bool {
+        self.batch.is_empty()
+    }
+
+    /// Finish the batch
+    pub fn build(self) -> PredictionBatch {
+        self.batch
+    }
+
+    fn check_count(&self, got: usize, kind: FeatureKind) -> CatBoostResult<()> {
+        let expected = match kind {
+            FeatureKind::Float => self.batch.float_features_count,
+            _ => self.batch.cat_features_count,
+        };
+        if got != expected {
+            let error = CatBoostError::invalid_input(
+                format!(
+                    "document {}: expected {} {} features, got {}",
+                    self.batch.len(),
+                    expected,
+                    kind,
+                    got
+                ),
+                expected,
+                got,
+            );
+            return Err(match self.model {
+                Some(model) => model.with_schema(error, kind),
+                None => error,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Documents stored in contiguous buffers, float features row by row and categorical features
+/// already hashed, so the batch can be scored repeatedly without copying or hashing again
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictionBatch {
+    docs: usize,
+    float_features_count: usize,
+    cat_features_count: usize,
+    float_features: Vec<f32>,
+    hashed_cat_features: Vec<i32>,
+}
+
+impl PredictionBatch {
+    /// Number of documents in the batch
+    pub fn len(&self) -> usize {
+        self.docs
+    }
+
+    /// Whether the batch holds no documents
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the float features of a single document
+    pub fn float_features(&self, doc: usize) -> Option<&[f32]> {
+        if doc >= self.docs {
+            return None;
+        }
+        let start = doc * self.float_features_count;
+        self.float_features
+            .get(start..start + self.float_features_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predict_batch() {
+        let model = Model::load("files/model.bin").unwrap();
+        let mut builder = model.batch_builder();
+        builder.push(&[-10.0, 5.0, 753.0], &["north"]).unwrap();
+        builder
+            .push_row(&Row::new().with(30.0).with(1.0).with(760.0).with("south"))
+            .unwrap();
+        assert!(builder.push(&[40.0, 0.1], &["south"]).is_err());
+        assert!(builder.push::<&str>(&[40.0, 0.1, 705.0], &[]).is_err());
+        assert_eq!(builder.len(), 2);
+
+        let batch = builder.build();
+        let expected = vec![0.9980003729960197, 0.00249414628534181];
+        assert_eq!(model.predict_batch(&batch).unwrap(), expected);
+        assert_eq!(model.predict_batch(&batch).unwrap(), expected);
+
+        let batch = model.batch_builder().build();
+        assert!(batch.is_empty());
+        assert!(model.predict_batch(&batch).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+//! Score CSV or TSV files with a CatBoost model, writing the predictions of each input row with
+//! one column per model dimension
+
+use catboost_rs::{CsvColumns, CsvOptions, Model, PredictionType};
+use clap::{Parser, ValueEnum};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Debug, Parser)]
+#[command(name = "catboost-score", version, about)]
+struct Args {
+    /// Model file in CatBoost binary (cbm) format
+    model: PathBuf,
+    /// Input file, standard input when left out
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+    /// Output file, standard output when left out
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Read and write tab separated values
+    #[arg(long, conflicts_with = "delimiter")]
+    tsv: bool,
+    /// Field delimiter
+    #[arg(long, default_value_t = ',')]
+    delimiter: char,
+    /// Input has no header row, columns must then be given by index
+    #[arg(long)]
+    no_header: bool,
+    /// Type of the written predictions
+    #[arg(long, value_enum, default_value_t = OutputType::Raw)]
+    prediction_type: OutputType,
+    /// Comma separated indices of the float feature columns, in model order. Columns are
+    /// matched to features by header name when left out.
+    #[arg(long, value_delimiter = ',')]
+    float_columns: Option<Vec<usize>>,
+    /// Comma separated indices of the categorical feature columns, in model order, needed along
+    /// with --float-columns for models with categorical features
+    #[arg(long, value_delimiter = ',', requires = "float_columns")]
+    cat_columns: Option<Vec<usize>>,
+    /// Number of rows scored per native prediction call
+    #[arg(long, default_value_t = 10_000)]
+    chunk_size: usize,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OutputType {
+    Raw,
+    Probability,
+    Class,
+    Exponent,
+}
+
+impl From<OutputType> for PredictionType {
+    fn from(output_type: OutputType) -> Self {
+        match output_type {
+            OutputType::Raw => PredictionType::RawFormulaVal,
+            OutputType::Probability => PredictionType::Probability,
+            OutputType::Class => PredictionType::Class,
+            OutputType::Exponent => PredictionType::Exponent,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("catboost-score: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let mut model = Model::load(&args.model)?;
+    model.set_prediction_type(args.prediction_type.into())?;
+
+    let delimiter = if args.tsv { '\t' } else { args.delimiter };
+    let delimiter = u8::try_from(delimiter).map_err(|_| "delimiter must be an ASCII character")?;
+    let columns = match args.float_columns {
+        Some(float_columns) => CsvColumns::Indices {
+            float_columns,
+            cat_columns: args.cat_columns.unwrap_or_default(),
+        },
+        None => CsvColumns::ByName,
+    };
+    let options = CsvOptions {
+        delimiter,
+        has_headers: !args.no_header,
+        columns,
+        chunk_size: args.chunk_size,
+    };
+
+    let reader: Box<dyn Read> = match &args.input {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let writer: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    model.score_csv(reader, writer, &options)?;
+    Ok(())
+}
@@ -0,0 +1,303 @@
+//! Probability calibration of binary classifiers, fitted on raw model outputs of held-out
+//! documents and their labels
+
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+
+/// Maximum number of Newton iterations when fitting Platt scaling
+const PLATT_MAX_ITERATIONS: usize = 100;
+
+/// Gradient norm below which Platt scaling is considered converged
+const PLATT_TOLERANCE: f64 = 1e-9;
+
+/// Mapping from raw formula values of a binary classifier to calibrated probabilities. Labels
+/// above 0.5 are treated as the positive class when fitting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Calibrator {
+    /// Platt scaling, a sigmoid of `a * raw + b`
+    Platt { a: f64, b: f64 },
+    /// Isotonic regression, a non-decreasing piecewise linear function through the given
+    /// points, constant beyond the first and last raw value
+    Isotonic {
+        raw: Vec<f64>,
+        probabilities: Vec<f64>,
+    },
+}
+
+impl Calibrator {
+    /// Fit Platt scaling by maximum likelihood, using Platt's smoothed targets so that a
+    /// separable calibration set does not yield infinite coefficients
+    pub fn fit_platt(raw_predictions: &[f64], labels: &[f32]) -> CatBoostResult<Self> {
+        check_calibration_set(raw_predictions, labels)?;
+        let positives = labels.iter().filter(|label| **label > 0.5).count() as f64;
+        let negatives = labels.len() as f64 - positives;
+        let positive_target = (positives + 1.0) / (positives + 2.0);
+        let negative_target = 1.0 / (negatives + 2.0);
+        let targets = labels
+            .iter()
+            .map(|label| {
+                if *label > 0.5 {
+                    positive_target
+                } else {
+                    negative_target
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let loss = |a: f64, b: f64| {
+            raw_predictions
+                .iter()
+                .zip(&targets)
+                .map(|(raw, target)| {
+                    let z = a * raw + b;
+                    softplus(z) - target * z
+                })
+                .sum::<f64>()
+        };
+
+        let (mut a, mut b) = (0.0, ((positives + 1.0) / (negatives + 1.0)).ln());
+        let mut current_loss = loss(a, b);
+        for _ in 0..PLATT_MAX_ITERATIONS {
+            let (mut g_a, mut g_b) = (0.0, 0.0);
+            // A small ridge keeps the Hessian invertible when all raw values are equal
+            let (mut h_aa, mut h_ab, mut h_bb) = (1e-12, 0.0, 1e-12);
+            for (raw, target) in raw_predictions.iter().zip(&targets) {
+                let p = sigmoid(a * raw + b);
+                let curvature = p * (1.0 - p);
+                g_a += (p - target) * raw;
+                g_b += p - target;
+                h_aa += curvature * raw * raw;
+                h_ab += curvature * raw;
+                h_bb += curvature;
+            }
+            if g_a.abs() < PLATT_TOLERANCE && g_b.abs() < PLATT_TOLERANCE {
+                break;
+            }
+
+            let determinant = h_aa * h_bb - h_ab * h_ab;
+            let d_a = -(h_bb * g_a - h_ab * g_b) / determinant;
+            let d_b = -(h_aa * g_b - h_ab * g_a) / determinant;
+            // Halve the Newton step until the loss decreases
+            let mut step = 1.0;
+            while step > 1e-10 {
+                let step_loss = loss(a + step * d_a, b + step * d_b);
+                if step_loss < current_loss {
+                    a += step * d_a;
+                    b += step * d_b;
+                    current_loss = step_loss;
+                    break;
+                }
+                step /= 2.0;
+            }
+            if step <= 1e-10 {
+                break;
+            }
+        }
+        Ok(Calibrator::Platt { a, b })
+    }
+
+    /// Fit isotonic regression with the pool adjacent violators algorithm
+    pub fn fit_isotonic(raw_predictions: &[f64], labels: &[f32]) -> CatBoostResult<Self> {
+        check_calibration_set(raw_predictions, labels)?;
+        let mut docs = (0..raw_predictions.len()).collect::<Vec<_>>();
+        docs.sort_by(|a, b| raw_predictions[*a].total_cmp(&raw_predictions[*b]));
+
+        // Blocks of (lowest raw value, highest raw value, positive share, document count),
+        // starting with one block per distinct raw value
+        let mut blocks: Vec<(f64, f64, f64, f64)> = Vec::new();
+        for doc in docs {
+            let raw = raw_predictions[doc];
+            let target = if labels[doc] > 0.5 { 1.0 } else { 0.0 };
+            match blocks.last_mut() {
+                Some(block) if block.1 == raw => {
+                    block.2 = (block.2 * block.3 + target) / (block.3 + 1.0);
+                    block.3 += 1.0;
+                }
+                _ => blocks.push((raw, raw, target, 1.0)),
+            }
+            while blocks.len() > 1 && blocks[blocks.len() - 2].2 >= blocks[blocks.len() - 1].2 {
+                let last = blocks.pop().unwrap();
+                let block = blocks.last_mut().unwrap();
+                block.2 = (block.2 * block.3 + last.2 * last.3) / (block.3 + last.3);
+                block.1 = last.1;
+                block.3 += last.3;
+            }
+        }
+
+        let mut raw = Vec::with_capacity(blocks.len() * 2);
+        let mut probabilities = Vec::with_capacity(blocks.len() * 2);
+        for (low, high, probability, _) in blocks {
+            raw.push(low);
+            probabilities.push(probability);
+            if high > low {
+                raw.push(high);
+                probabilities.push(probability);
+            }
+        }
+        Ok(Calibrator::Isotonic { raw, probabilities })
+    }
+
+    /// Map a raw formula value to a calibrated probability
+    pub fn calibrate(&self, raw_prediction: f64) -> f64 {
+        match self {
+            Calibrator::Platt { a, b } => sigmoid(a * raw_prediction + b),
+            Calibrator::Isotonic { raw, probabilities } => {
+                let upper = raw.partition_point(|x| *x <= raw_prediction);
+                if upper == 0 {
+                    return probabilities[0];
+                }
+                if upper == raw.len() {
+                    return probabilities[raw.len() - 1];
+                }
+                let (x0, x1) = (raw[upper - 1], raw[upper]);
+                let (y0, y1) = (probabilities[upper - 1], probabilities[upper]);
+                y0 + (y1 - y0) * (raw_prediction - x0) / (x1 - x0)
+            }
+        }
+    }
+}
+
+/// A binary classifier whose probabilities are passed through a fitted `Calibrator`
+#[derive(Debug)]
+pub struct CalibratedModel {
+    model: Model,
+    calibrator: Calibrator,
+}
+
+impl CalibratedModel {
+    /// Wrap a binary classifier, returning an error for models with more than one dimension
+    pub fn new(model: Model, calibrator: Calibrator) -> CatBoostResult<Self> {
+        if model.get_dimensions_count() != 1 {
+            return Err(CatBoostError::InvalidArgument(format!(
+                "calibration needs a model with one dimension, got {}",
+                model.get_dimensions_count()
+            )));
+        }
+        Ok(CalibratedModel { model, calibrator })
+    }
+
+    /// Get the wrapped model
+    pub fn model(&self) -> &Model {
+        &self.model
+    }
+
+    /// Get the calibrator applied to the model outputs
+    pub fn calibrator(&self) -> &Calibrator {
+        &self.calibrator
+    }
+
+    /// Calculate calibrated probabilities of the positive class on float features and string
+    /// categorical feature values. The calibrator is applied to raw values whatever prediction
+    /// type is set on the wrapped model.
+    pub fn predict_proba(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+    ) -> CatBoostResult<Vec<f64>> {
+        let raw_predictions = self
+            .model
+            .calc_raw_prediction(&float_features, &cat_features, 1)?;
+        Ok(raw_predictions
+            .into_iter()
+            .map(|raw| self.calibrator.calibrate(raw))
+            .collect())
+    }
+}
+
+fn check_calibration_set(raw_predictions: &[f64], labels: &[f32]) -> CatBoostResult<()> {
+    if raw_predictions.len() != labels.len() {
+        return Err(CatBoostError::invalid_input(
+            format!(
+                "got {} raw predictions and {} labels",
+                raw_predictions.len(),
+                labels.len()
+            ),
+            raw_predictions.len(),
+            labels.len(),
+        ));
+    }
+    if raw_predictions.is_empty() {
+        return Err(CatBoostError::InvalidArgument(
+            "calibration needs at least one document".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn sigmoid(z: f64) -> f64 {
+    1. / (1. + (-z).exp())
+}
+
+fn softplus(z: f64) -> f64 {
+    if z > 0.0 {
+        z + (-z).exp().ln_1p()
+    } else {
+        z.exp().ln_1p()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prediction_type::PredictionType;
+
+    #[test]
+    fn fit_platt() {
+        // Every fourth document below zero and every fourth above zero has the other label
+        let raw_predictions = (-20..20).map(|x| x as f64 / 4.0).collect::<Vec<_>>();
+        let labels = (-20..20)
+            .map(|x: i32| {
+                if (x >= 0) != (x.rem_euclid(4) == 0) {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let calibrator = Calibrator::fit_platt(&raw_predictions, &labels).unwrap();
+        let Calibrator::Platt { a, .. } = calibrator else {
+            panic!("expected Platt scaling");
+        };
+        assert!(a > 0.0);
+        assert!(calibrator.calibrate(-5.0) < 0.5);
+        assert!(calibrator.calibrate(5.0) > 0.5);
+        assert!(calibrator.calibrate(5.0) < 1.0);
+        assert!(Calibrator::fit_platt(&raw_predictions, &labels[1..]).is_err());
+    }
+
+    #[test]
+    fn fit_isotonic() {
+        let calibrator =
+            Calibrator::fit_isotonic(&[4.0, 1.0, 3.0, 2.0], &[1.0, 0.0, 0.0, 1.0]).unwrap();
+        assert_eq!(
+            calibrator,
+            Calibrator::Isotonic {
+                raw: vec![1.0, 2.0, 3.0, 4.0],
+                probabilities: vec![0.0, 0.5, 0.5, 1.0],
+            }
+        );
+        assert_eq!(calibrator.calibrate(0.0), 0.0);
+        assert_eq!(calibrator.calibrate(1.5), 0.25);
+        assert_eq!(calibrator.calibrate(2.5), 0.5);
+        assert_eq!(calibrator.calibrate(10.0), 1.0);
+        assert!(Calibrator::fit_isotonic(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn calibrated_model() {
+        let mut model = Model::load("files/model.bin").unwrap();
+        model
+            .set_prediction_type(PredictionType::Probability)
+            .unwrap();
+        let calibrator = Calibrator::Platt { a: 1.0, b: 0.0 };
+        let model = CalibratedModel::new(model, calibrator).unwrap();
+        let probabilities = model
+            .predict_proba(
+                vec![vec![-10.0, 5.0, 753.0]],
+                vec![vec![String::from("north")]],
+            )
+            .unwrap();
+        assert_eq!(probabilities, vec![sigmoid(0.9980003729960197)]);
+    }
+}
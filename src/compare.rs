@@ -0,0 +1,135 @@
+//! Comparison of the predictions of two models, e.g. to validate a retrained model before
+//! rolling it out
+
+use crate::batch::PredictionBatch;
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::{argmax, Model};
+use crate::prediction::MultiTargetPrediction;
+use crate::prediction_type::PredictionType;
+
+/// Differences between the raw predictions of two models on the same documents
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffReport {
+    /// Prediction of the second model minus the prediction of the first, per document and
+    /// dimension
+    pub deltas: MultiTargetPrediction,
+    /// Largest absolute difference over all documents and dimensions, 0 for an empty batch
+    pub max_abs_diff: f64,
+    /// Mean absolute difference over all documents and dimensions, 0 for an empty batch
+    pub mean_abs_diff: f64,
+    /// Share of documents assigned a different class, when both models are classifiers
+    pub disagreement_rate: Option<f64>,
+}
+
+/// Score `batch` with both models and compare the raw predictions, whatever prediction type is
+/// set on either model. The models must take the same features and have the same number of
+/// dimensions.
+pub fn diff_models(a: &Model, b: &Model, batch: &PredictionBatch) -> CatBoostResult<DiffReport> {
+    let dimensions = a.get_dimensions_count();
+    if b.get_dimensions_count() != dimensions {
+        return Err(CatBoostError::InvalidArgument(format!(
+            "models have {} and {} dimensions",
+            dimensions,
+            b.get_dimensions_count()
+        )));
+    }
+
+    let predictions_a = a.predict_batch_as(batch, PredictionType::RawFormulaVal)?;
+    let predictions_b = b.predict_batch_as(batch, PredictionType::RawFormulaVal)?;
+    Ok(diff_report(
+        &predictions_a,
+        &predictions_b,
+        dimensions,
+        a.is_classification() && b.is_classification(),
+    ))
+}
+
+/// Compare raw predictions holding `dimensions` values per document, counting class
+/// disagreements when both models are classifiers
+fn diff_report(
+    predictions_a: &[f64],
+    predictions_b: &[f64],
+    dimensions: usize,
+    classification: bool,
+) -> DiffReport {
+    let deltas = predictions_a
+        .iter()
+        .zip(predictions_b)
+        .map(|(a, b)| b - a)
+        .collect::<Vec<_>>();
+    let max_abs_diff = deltas
+        .iter()
+        .fold(0.0, |max: f64, delta| max.max(delta.abs()));
+    let mean_abs_diff = if deltas.is_empty() {
+        0.0
+    } else {
+        deltas.iter().map(|delta| delta.abs()).sum::<f64>() / deltas.len() as f64
+    };
+
+    let disagreement_rate = classification.then(|| {
+        let class = |prediction: &[f64]| match dimensions {
+            1 => usize::from(prediction[0] > 0.0),
+            _ => argmax(prediction),
+        };
+        let disagreements = predictions_a
+            .chunks(dimensions)
+            .zip(predictions_b.chunks(dimensions))
+            .filter(|(a, b)| class(a) != class(b))
+            .count();
+        let documents = predictions_a.len() / dimensions;
+        disagreements as f64 / documents.max(1) as f64
+    });
+
+    DiffReport {
+        deltas: MultiTargetPrediction::new(deltas, dimensions),
+        max_abs_diff,
+        mean_abs_diff,
+        disagreement_rate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_models() {
+        let a = Model::load("files/model.bin").unwrap();
+        let mut b = Model::load("files/model.bin").unwrap();
+        b.set_prediction_type(PredictionType::Probability).unwrap();
+        let mut builder = a.batch_builder();
+        builder.push(&[-10.0, 5.0, 753.0], &["north"]).unwrap();
+        builder.push(&[30.0, 1.0, 760.0], &["south"]).unwrap();
+        let batch = builder.build();
+
+        let report = super::diff_models(&a, &b, &batch).unwrap();
+        assert_eq!(report.deltas.as_flat(), &[0.0, 0.0]);
+        assert_eq!(report.max_abs_diff, 0.0);
+        assert_eq!(report.mean_abs_diff, 0.0);
+    }
+
+    #[test]
+    fn diff_report_binary() {
+        let report = diff_report(&[1.0, -2.0, 0.5, -0.1], &[0.5, 1.0, 0.5, 0.2], 1, true);
+        assert_eq!(
+            report.deltas.as_flat(),
+            &[-0.5, 3.0, 0.0, 0.30000000000000004]
+        );
+        assert_eq!(report.max_abs_diff, 3.0);
+        assert!((report.mean_abs_diff - 0.95).abs() < 1e-12);
+        assert_eq!(report.disagreement_rate, Some(0.5));
+
+        let report = diff_report(&[1.0, -2.0], &[-1.0, -2.0], 1, false);
+        assert_eq!(report.disagreement_rate, None);
+    }
+
+    #[test]
+    fn diff_report_multiclass() {
+        let a = [0.1, 0.7, 0.2, 0.5, 0.3, 0.2];
+        let b = [0.1, 0.2, 0.7, 0.6, 0.3, 0.1];
+        let report = diff_report(&a, &b, 3, true);
+        assert_eq!(report.deltas.len(), 2);
+        assert_eq!(report.disagreement_rate, Some(0.5));
+        assert_eq!(diff_report(&[], &[], 3, true).disagreement_rate, Some(0.0));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::error::CatBoostResult;
+use crate::hash::hash_cat_feature;
+use crate::model::{Model, PointerArray};
+
+/// Scratch buffers reused across prediction calls, so scoring in a hot loop does not allocate
+/// hashed categorical features and results on every call once the buffers have grown to the
+/// batch size. Row pointers into the inputs are rebuilt by each call, on the stack for small
+/// batches.
+#[derive(Debug, Default)]
+pub struct PredictionContext {
+    hashed_cat_features: Vec<i32>,
+    prediction: Vec<f64>,
+}
+
+impl PredictionContext {
+    /// Create a context with empty buffers
+    pub fn new() -> Self {
+        PredictionContext::default()
+    }
+
+    /// Calculate raw predictions of `model` on float features and string categorical feature
+    /// values. The returned slice holds as many values per document as the prediction type of
+    /// `model` gives, see `PredictionType`, and is overwritten by the next call.
+    pub fn predict<F, C, S>(
+        &mut self,
+        model: &Model,
+        float_features: &[F],
+        cat_features: &[C],
+    ) -> CatBoostResult<&[f64]>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        model.check_input_shape(float_features, cat_features)?;
+        let cat_features_count = model.get_cat_features_count();
+
+        self.hashed_cat_features.clear();
+        for doc in cat_features {
+            self.hashed_cat_features.extend(
+                doc.as_ref()
+                    .iter()
+                    .map(|cat_feature| hash_cat_feature(cat_feature.as_ref())),
+            );
+        }
+        let mut float_features_ptr = float_features
+            .iter()
+            .map(|doc| doc.as_ref().as_ptr())
+            .collect::<PointerArray<_>>();
+        let mut hashed_cat_features_ptr = (0..float_features.len())
+            .map(|doc| self.hashed_cat_features[doc * cat_features_count..].as_ptr())
+            .collect::<PointerArray<_>>();
+        self.prediction.clear();
+        self.prediction.resize(
+            float_features.len() * model.prediction_dimensions(model.get_prediction_type()),
+            0.0,
+        );
+
+        if !float_features.is_empty() {
+            model.calc_pointer_prediction_into(
+                &mut float_features_ptr,
+                model.get_float_features_count(),
+                &mut hashed_cat_features_ptr,
+                cat_features_count,
+                model.get_prediction_type(),
+                &mut self.prediction,
+            )?;
+        }
+        Ok(&self.prediction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predict_with_context() {
+        let model = Model::load("files/model.bin").unwrap();
+        let mut context = PredictionContext::new();
+
+        let prediction = context
+            .predict(
+                &model,
+                &[[-10.0, 5.0, 753.0], [30.0, 1.0, 760.0]],
+                &[["north"], ["south"]],
+            )
+            .unwrap();
+        assert_eq!(prediction, &[0.9980003729960197, 0.00249414628534181]);
+
+        let prediction = context
+            .predict(&model, &[[40.0, 0.1, 705.0]], &[["south"]])
+            .unwrap();
+        assert_eq!(prediction, &[-0.0013677527881450977]);
+
+        assert!(context
+            .predict(&model, &[[40.0, 0.1]], &[["south"]])
+            .is_err());
+        let prediction = context
+            .predict::<[f32; 3], [&str; 1], &str>(&model, &[], &[])
+            .unwrap();
+        assert!(prediction.is_empty());
+    }
+
+    #[test]
+    fn context_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<PredictionContext>();
+    }
+}
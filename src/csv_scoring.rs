@@ -0,0 +1,241 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+use std::io::{Read, Write};
+
+/// How CSV columns are mapped to model features
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvColumns {
+    /// Match header names against the feature names stored in the model
+    ByName,
+    /// Read float and categorical features from the given column indices, in model order
+    Indices {
+        float_columns: Vec<usize>,
+        cat_columns: Vec<usize>,
+    },
+}
+
+/// Options for `Model::score_csv`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    /// Field delimiter, used for both input and output
+    pub delimiter: u8,
+    /// Whether the input starts with a header row. A `prediction` header is written when set,
+    /// or `prediction_0`, `prediction_1`, ... when the prediction type gives several values per
+    /// document.
+    pub has_headers: bool,
+    /// Mapping of columns to model features
+    pub columns: CsvColumns,
+    /// Number of rows scored per native prediction call
+    pub chunk_size: usize,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            has_headers: true,
+            columns: CsvColumns::ByName,
+            chunk_size: 10_000,
+        }
+    }
+}
+
+impl Model {
+    /// Score CSV input from `reader` in chunks of `options.chunk_size` rows, writing the
+    /// predictions of each row to `writer` with the prediction type currently set on the model,
+    /// one column per value it gives for a document. Empty float fields are passed to the model
+    /// as missing values. Returns the number of scored rows.
+    pub fn score_csv<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        options: &CsvOptions,
+    ) -> CatBoostResult<usize> {
+        if options.chunk_size == 0 {
+            return Err(CatBoostError::InvalidArgument(
+                "chunk size must be greater than zero".into(),
+            ));
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .has_headers(options.has_headers)
+            .from_reader(reader);
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .from_writer(writer);
+
+        let (float_columns, cat_columns) = match &options.columns {
+            CsvColumns::ByName => {
+                if !options.has_headers {
+                    return Err(CatBoostError::InvalidArgument(
+                        "mapping columns by name requires a header row".into(),
+                    ));
+                }
+                let headers = reader.headers().map_err(csv_error)?.clone();
+                let (float_feature_names, cat_feature_names) = self.get_feature_layout()?;
+                let find_column = |name: &String| {
+                    headers
+                        .iter()
+                        .position(|header| header == name)
+                        .ok_or_else(|| {
+                            CatBoostError::InvalidArgument(format!(
+                                "CSV input has no column {}",
+                                name
+                            ))
+                        })
+                };
+                (
+                    float_feature_names
+                        .iter()
+                        .map(find_column)
+                        .collect::<CatBoostResult<Vec<_>>>()?,
+                    cat_feature_names
+                        .iter()
+                        .map(find_column)
+                        .collect::<CatBoostResult<Vec<_>>>()?,
+                )
+            }
+            CsvColumns::Indices {
+                float_columns,
+                cat_columns,
+            } => (float_columns.clone(), cat_columns.clone()),
+        };
+
+        let dimensions = self.prediction_dimensions(self.get_prediction_type());
+        if options.has_headers {
+            let headers = if dimensions == 1 {
+                vec![String::from("prediction")]
+            } else {
+                (0..dimensions)
+                    .map(|dimension| format!("prediction_{}", dimension))
+                    .collect()
+            };
+            writer.write_record(&headers).map_err(csv_error)?;
+        }
+
+        let mut float_chunk = Vec::with_capacity(options.chunk_size);
+        let mut cat_chunk = Vec::with_capacity(options.chunk_size);
+        let mut rows = 0;
+        for record in reader.records() {
+            let record = record.map_err(csv_error)?;
+            let field = |column: usize| {
+                record.get(column).ok_or_else(|| {
+                    CatBoostError::InvalidArgument(format!("row {}: no column {}", rows, column))
+                })
+            };
+
+            float_chunk.push(
+                float_columns
+                    .iter()
+                    .map(|&column| field(column).and_then(|value| parse_float(value, rows, column)))
+                    .collect::<CatBoostResult<Vec<_>>>()?,
+            );
+            cat_chunk.push(
+                cat_columns
+                    .iter()
+                    .map(|&column| field(column).map(String::from))
+                    .collect::<CatBoostResult<Vec<_>>>()?,
+            );
+            rows += 1;
+
+            if float_chunk.len() == options.chunk_size {
+                self.write_csv_chunk(&mut writer, &float_chunk, &cat_chunk)?;
+                float_chunk.clear();
+                cat_chunk.clear();
+            }
+        }
+        if !float_chunk.is_empty() {
+            self.write_csv_chunk(&mut writer, &float_chunk, &cat_chunk)?;
+        }
+
+        writer
+            .flush()
+            .map_err(|e| CatBoostError::Io(e.to_string()))?;
+        Ok(rows)
+    }
+
+    fn write_csv_chunk<W: Write>(
+        &self,
+        writer: &mut csv::Writer<W>,
+        float_chunk: &[Vec<f32>],
+        cat_chunk: &[Vec<String>],
+    ) -> CatBoostResult<()> {
+        let dimensions = self.prediction_dimensions(self.get_prediction_type());
+        let predictions = self.calc_prediction(float_chunk, cat_chunk, dimensions)?;
+        for doc_predictions in predictions.chunks(dimensions) {
+            writer
+                .write_record(doc_predictions.iter().map(f64::to_string))
+                .map_err(csv_error)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_float(value: &str, row: usize, column: usize) -> CatBoostResult<f32> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(f32::NAN);
+    }
+    value.parse().map_err(|_| {
+        CatBoostError::Parse(format!(
+            "row {}: cannot parse {:?} in column {} as a float",
+            row, value, column
+        ))
+    })
+}
+
+fn csv_error(error: csv::Error) -> CatBoostError {
+    if error.is_io_error() {
+        CatBoostError::Io(error.to_string())
+    } else {
+        CatBoostError::Parse(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_csv_by_index() {
+        let model = Model::load("files/model.bin").unwrap();
+        let input = "a,b,c,d\n-10.0,5.0,753.0,north\n30.0,1.0,760.0,south\n40.0,0.1,705.0,south\n";
+        let options = CsvOptions {
+            columns: CsvColumns::Indices {
+                float_columns: vec![0, 1, 2],
+                cat_columns: vec![3],
+            },
+            chunk_size: 2,
+            ..CsvOptions::default()
+        };
+
+        let mut output = Vec::new();
+        let rows = model
+            .score_csv(input.as_bytes(), &mut output, &options)
+            .unwrap();
+
+        assert_eq!(rows, 3);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "prediction\n0.9980003729960197\n0.00249414628534181\n-0.0013677527881450977\n"
+        );
+    }
+
+    #[test]
+    fn score_csv_rejects_unparsable_floats() {
+        let model = Model::load("files/model.bin").unwrap();
+        let options = CsvOptions {
+            has_headers: false,
+            columns: CsvColumns::Indices {
+                float_columns: vec![0, 1, 2],
+                cat_columns: vec![3],
+            },
+            ..CsvOptions::default()
+        };
+
+        let mut output = Vec::new();
+        let result = model.score_csv("x,5.0,753.0,north\n".as_bytes(), &mut output, &options);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,507 @@
+//! Feature drift detection, comparing the distribution of scored features against a reference
+//! profile captured e.g. on the training data
+
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::feature::FeatureKind;
+use crate::model::{check_doc_count, Model};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Number of values kept per float feature to estimate quantiles
+const SAMPLE_CAPACITY: usize = 1024;
+
+/// Number of reference quantile bins float features are compared on
+const PSI_BINS: usize = 10;
+
+/// Share assumed for bins without any documents, keeping the stability index finite
+const PSI_MIN_SHARE: f64 = 1e-4;
+
+/// Statistics of the features of all documents added to it, to be compared against a
+/// reference profile with `DriftProfile::compare`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftProfile {
+    float_feature_names: Vec<String>,
+    cat_feature_names: Vec<String>,
+    float_features: Vec<FloatStats>,
+    cat_features: Vec<BTreeMap<String, u64>>,
+}
+
+/// Running statistics of a float feature. Quantiles are estimated from an evenly spaced sample
+/// of at most 1024 values, so memory use does not grow with the number of documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatStats {
+    count: u64,
+    missing: u64,
+    mean: f64,
+    m2: f64,
+    sample: Vec<f32>,
+    stride: u64,
+}
+
+/// Drift of a single feature between a reference profile and a current one
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureDrift {
+    /// Name of the feature
+    pub name: String,
+    /// Population stability index of the current distribution against the reference one
+    pub psi: f64,
+    /// Whether `psi` exceeds the threshold passed to `DriftProfile::compare`
+    pub drifted: bool,
+}
+
+impl DriftProfile {
+    /// Create an empty profile for the features of `model`
+    pub fn new(model: &Model) -> CatBoostResult<Self> {
+        let (float_feature_names, cat_feature_names) = model.get_feature_layout()?;
+        Ok(DriftProfile::with_feature_names(
+            float_feature_names,
+            cat_feature_names,
+        ))
+    }
+
+    /// Create an empty profile for float and categorical features with the given names
+    pub fn with_feature_names(
+        float_feature_names: Vec<String>,
+        cat_feature_names: Vec<String>,
+    ) -> Self {
+        DriftProfile {
+            float_features: vec![FloatStats::default(); float_feature_names.len()],
+            cat_features: vec![BTreeMap::new(); cat_feature_names.len()],
+            float_feature_names,
+            cat_feature_names,
+        }
+    }
+
+    /// Add the features of a batch of documents, as passed to the prediction methods. NaN float
+    /// values are counted as missing.
+    pub fn update<F, C, S>(
+        &mut self,
+        float_features: &[F],
+        cat_features: &[C],
+    ) -> CatBoostResult<()>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        check_doc_count(
+            float_features.len(),
+            cat_features.len(),
+            FeatureKind::Categorical,
+        )?;
+        check_rows(
+            float_features,
+            self.float_feature_names.len(),
+            FeatureKind::Float,
+        )?;
+        check_rows(
+            cat_features,
+            self.cat_feature_names.len(),
+            FeatureKind::Categorical,
+        )?;
+        for doc in float_features {
+            for (stats, value) in self.float_features.iter_mut().zip(doc.as_ref()) {
+                stats.push(*value);
+            }
+        }
+        for doc in cat_features {
+            for (frequencies, value) in self.cat_features.iter_mut().zip(doc.as_ref()) {
+                *frequencies.entry(value.as_ref().to_owned()).or_insert(0) += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the statistics of a float feature
+    pub fn float_stats(&self, name: &str) -> Option<&FloatStats> {
+        let index = self.float_feature_names.iter().position(|n| n == name)?;
+        Some(&self.float_features[index])
+    }
+
+    /// Get the number of documents with each value of a categorical feature
+    pub fn category_counts(&self, name: &str) -> Option<&BTreeMap<String, u64>> {
+        let index = self.cat_feature_names.iter().position(|n| n == name)?;
+        Some(&self.cat_features[index])
+    }
+
+    /// Compare this profile against `reference`, returning the population stability index of
+    /// every feature. Float features are binned on the deciles of the reference, with missing
+    /// values in a bin of their own. Features with an index above `threshold` are flagged, 0.2
+    /// being a common choice for a significant shift.
+    pub fn compare(
+        &self,
+        reference: &DriftProfile,
+        threshold: f64,
+    ) -> CatBoostResult<Vec<FeatureDrift>> {
+        if self.float_feature_names != reference.float_feature_names
+            || self.cat_feature_names != reference.cat_feature_names
+        {
+            return Err(CatBoostError::InvalidArgument(
+                "profiles describe different features".into(),
+            ));
+        }
+
+        let float_drift = self
+            .float_feature_names
+            .iter()
+            .zip(self.float_features.iter().zip(&reference.float_features))
+            .map(|(name, (current, reference))| (name, current.psi(reference)));
+        let cat_drift = self
+            .cat_feature_names
+            .iter()
+            .zip(self.cat_features.iter().zip(&reference.cat_features))
+            .map(|(name, (current, reference))| (name, category_psi(current, reference)));
+        Ok(float_drift
+            .chain(cat_drift)
+            .map(|(name, psi)| FeatureDrift {
+                name: name.clone(),
+                psi,
+                drifted: psi > threshold,
+            })
+            .collect())
+    }
+
+    /// Convert the profile to JSON, e.g. to store a reference profile next to the model
+    pub fn to_json(&self) -> Value {
+        let float_features = self
+            .float_feature_names
+            .iter()
+            .zip(&self.float_features)
+            .map(|(name, stats)| {
+                json!({
+                    "name": name,
+                    "count": stats.count,
+                    "missing": stats.missing,
+                    "mean": stats.mean,
+                    "m2": stats.m2,
+                    "sample": stats.sample,
+                    "stride": stats.stride,
+                })
+            })
+            .collect::<Vec<_>>();
+        let cat_features = self
+            .cat_feature_names
+            .iter()
+            .zip(&self.cat_features)
+            .map(|(name, counts)| json!({ "name": name, "counts": counts }))
+            .collect::<Vec<_>>();
+        json!({ "float_features": float_features, "cat_features": cat_features })
+    }
+
+    /// Read a profile written by `DriftProfile::to_json`
+    pub fn from_json(value: &Value) -> CatBoostResult<Self> {
+        let mut profile = DriftProfile::with_feature_names(Vec::new(), Vec::new());
+        for feature in json_array(value, "float_features")? {
+            let sample = json_array(feature, "sample")?
+                .iter()
+                .map(|value| json_f64(value).map(|value| value as f32))
+                .collect::<CatBoostResult<Vec<_>>>()?;
+            profile.float_feature_names.push(json_str(feature, "name")?);
+            profile.float_features.push(FloatStats {
+                count: json_u64(feature, "count")?,
+                missing: json_u64(feature, "missing")?,
+                mean: json_f64(&feature["mean"])?,
+                m2: json_f64(&feature["m2"])?,
+                sample,
+                stride: json_u64(feature, "stride")?.max(1),
+            });
+        }
+        for feature in json_array(value, "cat_features")? {
+            let counts = feature["counts"]
+                .as_object()
+                .ok_or_else(|| json_error("counts"))?
+                .iter()
+                .map(|(category, count)| {
+                    let count = count.as_u64().ok_or_else(|| json_error("counts"))?;
+                    Ok((category.clone(), count))
+                })
+                .collect::<CatBoostResult<BTreeMap<_, _>>>()?;
+            profile.cat_feature_names.push(json_str(feature, "name")?);
+            profile.cat_features.push(counts);
+        }
+        Ok(profile)
+    }
+}
+
+impl Default for FloatStats {
+    fn default() -> Self {
+        FloatStats {
+            count: 0,
+            missing: 0,
+            mean: 0.0,
+            m2: 0.0,
+            sample: Vec::new(),
+            stride: 1,
+        }
+    }
+}
+
+impl FloatStats {
+    /// Get the number of non-missing values
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Get the number of missing values
+    pub fn missing(&self) -> u64 {
+        self.missing
+    }
+
+    /// Get the mean of the non-missing values, or NaN if there are none
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            return f64::NAN;
+        }
+        self.mean
+    }
+
+    /// Get the population standard deviation of the non-missing values, or NaN if there are
+    /// none
+    pub fn std(&self) -> f64 {
+        if self.count == 0 {
+            return f64::NAN;
+        }
+        (self.m2 / self.count as f64).sqrt()
+    }
+
+    /// Estimate the `q` quantile of the non-missing values, for `q` between 0 and 1
+    pub fn quantile(&self, q: f64) -> Option<f32> {
+        let sorted = self.sorted_sample();
+        let last = sorted.len().checked_sub(1)?;
+        Some(sorted[(q.clamp(0.0, 1.0) * last as f64).round() as usize])
+    }
+
+    fn push(&mut self, value: f32) {
+        if value.is_nan() {
+            self.missing += 1;
+            return;
+        }
+
+        // Welford's online update of the mean and the sum of squared deviations
+        let value_f64 = f64::from(value);
+        let delta = value_f64 - self.mean;
+        self.mean += delta / (self.count + 1) as f64;
+        self.m2 += delta * (value_f64 - self.mean);
+
+        // Keep every stride-th value, halving the sample and doubling the stride once it is full
+        if self.count.is_multiple_of(self.stride) {
+            self.sample.push(value);
+            if self.sample.len() == SAMPLE_CAPACITY {
+                self.sample = self.sample.iter().step_by(2).copied().collect();
+                self.stride *= 2;
+            }
+        }
+        self.count += 1;
+    }
+
+    fn sorted_sample(&self) -> Vec<f32> {
+        let mut sorted = self.sample.clone();
+        sorted.sort_by(f32::total_cmp);
+        sorted
+    }
+
+    /// Population stability index of these values against `reference`, on the deciles of the
+    /// reference plus a bin of missing values. The index is 0 when either side saw no documents
+    /// at all, a feature that became entirely missing is compared on its missing share.
+    fn psi(&self, reference: &FloatStats) -> f64 {
+        if self.count + self.missing == 0 || reference.count + reference.missing == 0 {
+            return 0.0;
+        }
+        // Without present reference values there are no deciles, present values then all fall
+        // in the first bin and only the missing share can differ
+        let reference_sorted = reference.sorted_sample();
+        let edges = if reference_sorted.is_empty() {
+            Vec::new()
+        } else {
+            (1..PSI_BINS)
+                .map(|bin| reference_sorted[bin * reference_sorted.len() / PSI_BINS])
+                .collect::<Vec<_>>()
+        };
+
+        let shares = |stats: &FloatStats| {
+            let present = stats.count as f64 / (stats.count + stats.missing) as f64;
+            let mut shares = vec![0.0; PSI_BINS + 1];
+            for value in &stats.sample {
+                shares[edges.partition_point(|edge| edge <= value)] += 1.0;
+            }
+            if !stats.sample.is_empty() {
+                for share in &mut shares[..PSI_BINS] {
+                    *share *= present / stats.sample.len() as f64;
+                }
+            }
+            shares[PSI_BINS] = 1.0 - present;
+            shares
+        };
+        psi(&shares(self), &shares(reference))
+    }
+}
+
+/// Population stability index of category frequencies against reference ones
+fn category_psi(current: &BTreeMap<String, u64>, reference: &BTreeMap<String, u64>) -> f64 {
+    let current_total = current.values().sum::<u64>() as f64;
+    let reference_total = reference.values().sum::<u64>() as f64;
+    if current_total == 0.0 || reference_total == 0.0 {
+        return 0.0;
+    }
+
+    let categories = current
+        .keys()
+        .chain(reference.keys())
+        .collect::<BTreeSet<_>>();
+    let shares = |counts: &BTreeMap<String, u64>, total: f64| {
+        categories
+            .iter()
+            .map(|category| {
+                counts
+                    .get(*category)
+                    .map_or(0.0, |count| *count as f64 / total)
+            })
+            .collect::<Vec<_>>()
+    };
+    psi(
+        &shares(current, current_total),
+        &shares(reference, reference_total),
+    )
+}
+
+fn psi(current_shares: &[f64], reference_shares: &[f64]) -> f64 {
+    current_shares
+        .iter()
+        .zip(reference_shares)
+        .map(|(current, reference)| {
+            let current = current.max(PSI_MIN_SHARE);
+            let reference = reference.max(PSI_MIN_SHARE);
+            (current - reference) * (current / reference).ln()
+        })
+        .sum()
+}
+
+fn check_rows<R: AsRef<[T]>, T>(
+    docs: &[R],
+    expected: usize,
+    kind: FeatureKind,
+) -> CatBoostResult<()> {
+    for (doc_index, doc) in docs.iter().enumerate() {
+        let count = doc.as_ref().len();
+        if count != expected {
+            return Err(CatBoostError::invalid_input(
+                format!(
+                    "document {}: expected {} {} features, got {}",
+                    doc_index, expected, kind, count
+                ),
+                expected,
+                count,
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn json_array<'a>(value: &'a Value, key: &str) -> CatBoostResult<&'a Vec<Value>> {
+    value[key].as_array().ok_or_else(|| json_error(key))
+}
+
+fn json_str(value: &Value, key: &str) -> CatBoostResult<String> {
+    value[key]
+        .as_str()
+        .map(str::to_owned)
+        .ok_or_else(|| json_error(key))
+}
+
+fn json_u64(value: &Value, key: &str) -> CatBoostResult<u64> {
+    value[key].as_u64().ok_or_else(|| json_error(key))
+}
+
+fn json_f64(value: &Value) -> CatBoostResult<f64> {
+    value.as_f64().ok_or_else(|| json_error("number"))
+}
+
+fn json_error(key: &str) -> CatBoostError {
+    CatBoostError::Parse(format!("invalid drift profile: bad or missing {}", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(offset: f32, categories: &[&str]) -> DriftProfile {
+        let mut profile =
+            DriftProfile::with_feature_names(vec![String::from("x")], vec![String::from("c")]);
+        let float_features = (0..5000)
+            .map(|i| vec![(i % 100) as f32 + offset])
+            .collect::<Vec<_>>();
+        let cat_features = (0..5000)
+            .map(|i| vec![categories[i % categories.len()]])
+            .collect::<Vec<_>>();
+        profile.update(&float_features, &cat_features).unwrap();
+        profile
+    }
+
+    #[test]
+    fn float_stats() {
+        let profile = profile(0.0, &["a"]);
+        let stats = profile.float_stats("x").unwrap();
+        assert_eq!(stats.count(), 5000);
+        assert!((stats.mean() - 49.5).abs() < 1e-9);
+        assert!((stats.std() - (9999.0f64 / 12.0).sqrt()).abs() < 1e-9);
+        assert!((stats.quantile(0.5).unwrap() - 50.0).abs() <= 2.0);
+        assert!(stats.sample.len() < SAMPLE_CAPACITY);
+        assert_eq!(profile.category_counts("c").unwrap()["a"], 5000);
+    }
+
+    #[test]
+    fn compare_profiles() {
+        let reference = profile(0.0, &["a", "b"]);
+        let drift = profile(0.0, &["a", "b"]).compare(&reference, 0.2).unwrap();
+        assert!(drift.iter().all(|feature| !feature.drifted));
+
+        let drift = profile(50.0, &["a", "c"]).compare(&reference, 0.2).unwrap();
+        assert_eq!(drift[0].name, "x");
+        assert!(drift.iter().all(|feature| feature.drifted));
+
+        let other = DriftProfile::with_feature_names(Vec::new(), Vec::new());
+        assert!(other.compare(&reference, 0.2).is_err());
+        assert!(profile(0.0, &["a"])
+            .update(&[vec![1.0, 2.0]], &[vec!["a"]])
+            .is_err());
+        assert!(profile(0.0, &["a"])
+            .update(&[vec![1.0], vec![2.0]], &[vec!["a"]])
+            .is_err());
+    }
+
+    #[test]
+    fn compare_all_missing() {
+        let reference = profile(0.0, &["a"]);
+        let mut current =
+            DriftProfile::with_feature_names(vec![String::from("x")], vec![String::from("c")]);
+        current
+            .update(&vec![vec![f32::NAN]; 100], &vec![vec!["a"]; 100])
+            .unwrap();
+        let drift = current.compare(&reference, 0.2).unwrap();
+        assert_eq!(drift[0].name, "x");
+        assert!(drift[0].drifted);
+        assert!(!drift[1].drifted);
+        assert!(reference.compare(&current, 0.2).unwrap()[0].drifted);
+
+        let empty =
+            DriftProfile::with_feature_names(vec![String::from("x")], vec![String::from("c")]);
+        assert_eq!(empty.compare(&reference, 0.2).unwrap()[0].psi, 0.0);
+    }
+
+    #[test]
+    fn profile_to_json() {
+        let profile = profile(0.5, &["a", "b"]);
+        let restored = DriftProfile::from_json(&profile.to_json()).unwrap();
+        let (stats, restored_stats) = (
+            profile.float_stats("x").unwrap(),
+            restored.float_stats("x").unwrap(),
+        );
+        assert_eq!(restored_stats.count(), stats.count());
+        assert!((restored_stats.mean() - stats.mean()).abs() < 1e-9);
+        assert_eq!(restored_stats.sample, stats.sample);
+        assert_eq!(restored.category_counts("c"), profile.category_counts("c"));
+        let drift = restored.compare(&profile, 0.2).unwrap();
+        assert!(drift.iter().all(|feature| feature.psi.abs() < 1e-9));
+        assert!(DriftProfile::from_json(&json!({})).is_err());
+    }
+}
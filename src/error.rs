@@ -1,15 +1,67 @@
 use catboost_sys;
 use std::ffi::CStr;
-use std::fmt;
+use std::path::{Path, PathBuf};
 
 pub type CatBoostResult<T> = std::result::Result<T, CatBoostError>;
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct CatBoostError {
-    description: String,
+/// Error returned by the crate, grouped by the kind of failure so callers can match on it
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum CatBoostError {
+    /// A model could not be read or loaded
+    #[error("{0}")]
+    ModelLoad(String),
+    /// Input holds a different number of documents, features or values than expected
+    #[error("{description}")]
+    InvalidInput {
+        description: String,
+        expected: usize,
+        got: usize,
+    },
+    /// An argument cannot be used, e.g. a missing feature, a value of the wrong type or an
+    /// unsupported option
+    #[error("{0}")]
+    InvalidArgument(String),
+    /// Input data such as a CSV or pool file is malformed
+    #[error("{0}")]
+    Parse(String),
+    /// A path contains a NUL byte, which no file system accepts
+    #[error("path {} contains a NUL byte", .0.display())]
+    NulInPath(PathBuf),
+    /// Reading, writing or watching a file failed
+    #[error("{0}")]
+    Io(String),
+    /// A blocking prediction task panicked or was cancelled
+    #[error("{0}")]
+    TaskFailed(String),
+    /// CatBoost reported an error or returned inconsistent data, with the message from
+    /// `GetErrorString` for failed calls
+    #[error("{0}")]
+    NativeError(String),
 }
 
 impl CatBoostError {
+    /// Create an error for input with an unexpected number of documents, features or values
+    pub(crate) fn invalid_input<S: Into<String>>(
+        description: S,
+        expected: usize,
+        got: usize,
+    ) -> Self {
+        CatBoostError::InvalidInput {
+            description: description.into(),
+            expected,
+            got,
+        }
+    }
+
+    /// Check that a path can be passed to the file system, returning `NulInPath` instead of
+    /// letting a NUL byte surface as an opaque I/O error
+    pub(crate) fn check_path(path: &Path) -> CatBoostResult<()> {
+        if path.as_os_str().to_string_lossy().contains('\0') {
+            return Err(CatBoostError::NulInPath(path.to_path_buf()));
+        }
+        Ok(())
+    }
+
     /// Check the return value from an CatBoost FFI call, and return the last error message on error.
     /// Return values of true are treated as success, returns values of false are treated as errors.
     pub fn check_return_value(ret_val: bool) -> CatBoostResult<()> {
@@ -23,17 +75,30 @@ impl CatBoostError {
     /// Fetch current error message from CatBoost.
     fn fetch_catboost_error() -> Self {
         let c_str = unsafe { CStr::from_ptr(catboost_sys::GetErrorString()) };
-        let str_slice = c_str.to_str().unwrap();
-        CatBoostError {
-            description: str_slice.to_owned(),
-        }
+        CatBoostError::NativeError(c_str.to_string_lossy().into_owned())
     }
 }
 
-impl fmt::Display for CatBoostError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.description)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_composable<E: std::error::Error + Send + Sync + 'static>() {}
+
+    #[test]
+    fn error_composes_with_error_handling_crates() {
+        assert_composable::<CatBoostError>();
+
+        let error: Box<dyn std::error::Error + Send + Sync> = Box::new(
+            CatBoostError::invalid_input("document 0: expected 3 float features, got 2", 3, 2),
+        );
+        assert_eq!(
+            error.to_string(),
+            "document 0: expected 3 float features, got 2"
+        );
+        assert_eq!(
+            CatBoostError::NulInPath(PathBuf::from("model\0.bin")).to_string(),
+            "path model\0.bin contains a NUL byte"
+        );
     }
 }
-
-impl std::error::Error for CatBoostError {}
\ No newline at end of file
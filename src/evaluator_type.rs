@@ -0,0 +1,21 @@
+/// Formula evaluator backend used for prediction
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EvaluatorType {
+    /// Native CPU evaluator, the default
+    Cpu,
+    /// CUDA evaluator, available when CatBoost is built with CUDA support
+    Gpu,
+}
+
+impl EvaluatorType {
+    /// Convert a value of the native evaluator type enum
+    pub(crate) fn from_native(
+        evaluator_type: catboost_sys::ECatBoostApiFormulaEvaluatorType,
+    ) -> Option<Self> {
+        match evaluator_type {
+            catboost_sys::ECatBoostApiFormulaEvaluatorType_CBA_FET_CPU => Some(EvaluatorType::Cpu),
+            catboost_sys::ECatBoostApiFormulaEvaluatorType_CBA_FET_GPU => Some(EvaluatorType::Gpu),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+use std::fmt;
+
+/// Value of a single feature of a document
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeatureValue {
+    /// Value of a float feature
+    Float(f32),
+    /// Value of a categorical feature
+    Cat(String),
+    /// Value of a text feature
+    Text(String),
+    /// Vector of an embedding feature
+    Embedding(Vec<f32>),
+    /// Missing value, passed to the model as NaN for float features
+    Missing,
+}
+
+impl From<f32> for FeatureValue {
+    fn from(value: f32) -> Self {
+        FeatureValue::Float(value)
+    }
+}
+
+impl From<Option<f32>> for FeatureValue {
+    fn from(value: Option<f32>) -> Self {
+        value.map_or(FeatureValue::Missing, FeatureValue::Float)
+    }
+}
+
+impl From<String> for FeatureValue {
+    fn from(value: String) -> Self {
+        FeatureValue::Cat(value)
+    }
+}
+
+impl From<&str> for FeatureValue {
+    fn from(value: &str) -> Self {
+        FeatureValue::Cat(value.to_owned())
+    }
+}
+
+impl From<Vec<f32>> for FeatureValue {
+    fn from(value: Vec<f32>) -> Self {
+        FeatureValue::Embedding(value)
+    }
+}
+
+/// Kind of the features of a document, as named in input shape errors
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum FeatureKind {
+    Float,
+    Categorical,
+    Text,
+    Embedding,
+}
+
+impl fmt::Display for FeatureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FeatureKind::Float => "float",
+            FeatureKind::Categorical => "categorical",
+            FeatureKind::Text => "text",
+            FeatureKind::Embedding => "embedding",
+        })
+    }
+}
+
+/// Feature values of a single document. Values of each kind are taken in the order the model
+/// stores features of that kind, so float, categorical, text and embedding values may be
+/// interleaved freely.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Row {
+    values: Vec<FeatureValue>,
+}
+
+impl Row {
+    /// Create a document without feature values
+    pub fn new() -> Self {
+        Row::default()
+    }
+
+    /// Add a feature value to the document, e.g. `Row::new().with(1.5).with("north")`
+    pub fn with<V: Into<FeatureValue>>(mut self, value: V) -> Self {
+        self.push(value);
+        self
+    }
+
+    /// Add a feature value to the document
+    pub fn push<V: Into<FeatureValue>>(&mut self, value: V) {
+        self.values.push(value.into());
+    }
+
+    /// Feature values of the document, in the order they were added
+    pub fn values(&self) -> &[FeatureValue] {
+        &self.values
+    }
+}
+
+impl From<Vec<FeatureValue>> for Row {
+    fn from(values: Vec<FeatureValue>) -> Self {
+        Row { values }
+    }
+}
+
+impl FromIterator<FeatureValue> for Row {
+    fn from_iter<I: IntoIterator<Item = FeatureValue>>(iter: I) -> Self {
+        Row {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Model {
+    /// Calculate raw model predictions on documents given as rows of feature values. Each value
+    /// is routed to the float, categorical, text or embedding features of the model by its
+    /// kind, and `FeatureValue::Missing` is passed as a NaN float feature. An error is returned
+    /// when a row holds a different number of values of some kind than the model expects.
+    pub fn predict_rows(&self, rows: &[Row]) -> CatBoostResult<Vec<f64>> {
+        let mut float_features = Vec::with_capacity(rows.len());
+        let mut cat_features = Vec::with_capacity(rows.len());
+        let mut text_features = Vec::with_capacity(rows.len());
+        let mut embedding_features = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut doc_float_features = Vec::with_capacity(self.get_float_features_count());
+            let mut doc_cat_features = Vec::with_capacity(self.get_cat_features_count());
+            let mut doc_text_features = Vec::new();
+            let mut doc_embedding_features = Vec::new();
+            for value in row.values() {
+                match value {
+                    FeatureValue::Float(value) => doc_float_features.push(*value),
+                    FeatureValue::Missing => doc_float_features.push(f32::NAN),
+                    FeatureValue::Cat(value) => doc_cat_features.push(value.clone()),
+                    FeatureValue::Text(value) => doc_text_features.push(value.clone()),
+                    FeatureValue::Embedding(value) => doc_embedding_features.push(value.clone()),
+                }
+            }
+            float_features.push(doc_float_features);
+            cat_features.push(doc_cat_features);
+            text_features.push(doc_text_features);
+            embedding_features.push(doc_embedding_features);
+        }
+
+        if self.get_text_features_count() == 0 && self.get_embedding_features_count() == 0 {
+            // Keep to the plain evaluator entry point, but still reject stray values
+            if let Some(doc_index) = text_features
+                .iter()
+                .zip(&embedding_features)
+                .position(|(texts, embeddings)| !texts.is_empty() || !embeddings.is_empty())
+            {
+                return Err(CatBoostError::InvalidArgument(format!(
+                    "document {}: model has no text or embedding features",
+                    doc_index
+                )));
+            }
+            return self.calc_prediction(
+                &float_features,
+                &cat_features,
+                self.prediction_dimensions(self.get_prediction_type()),
+            );
+        }
+        self.calc_prediction_with_text_and_embeddings(
+            &float_features,
+            &cat_features,
+            &text_features,
+            &embedding_features,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::check_doc_count;
+
+    #[test]
+    fn feature_kind_names() {
+        assert_eq!(FeatureKind::Float.to_string(), "float");
+        assert_eq!(FeatureKind::Categorical.to_string(), "categorical");
+        let error = check_doc_count(2, 1, FeatureKind::Text).unwrap_err();
+        assert!(error.to_string().contains("text features for 1 documents"));
+    }
+
+    #[test]
+    fn predict_rows() {
+        let model = Model::load("files/model.bin").unwrap();
+        let rows = vec![
+            Row::new().with(-10.0).with("north").with(5.0).with(753.0),
+            vec![
+                FeatureValue::from(30.0),
+                FeatureValue::from(1.0),
+                FeatureValue::from(760.0),
+                FeatureValue::from("south"),
+            ]
+            .into(),
+        ];
+        assert_eq!(
+            model.predict_rows(&rows).unwrap(),
+            vec![0.9980003729960197, 0.00249414628534181]
+        );
+
+        let rows = vec![Row::new().with(-10.0).with(5.0).with("north")];
+        assert!(model.predict_rows(&rows).is_err());
+        let rows = vec![Row::new()
+            .with(-10.0)
+            .with(5.0)
+            .with(753.0)
+            .with("north")
+            .with(FeatureValue::Text(String::from("cold")))];
+        assert!(model.predict_rows(&rows).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::error::CatBoostError;
+use crate::model::Model;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// Messages and service definitions generated from `proto/catboost.proto`
+pub mod proto {
+    tonic::include_proto!("catboost");
+}
+
+use proto::scoring_server::{Scoring, ScoringServer};
+use proto::{
+    Document, ModelInfoRequest, ModelInfoResponse, PredictBatchRequest, PredictBatchResponse,
+    PredictRequest, PredictResponse,
+};
+
+/// gRPC `Scoring` service answering requests with a loaded model
+#[derive(Debug, Clone)]
+pub struct ScoringService {
+    model: Arc<Model>,
+}
+
+impl ScoringService {
+    /// Create a service scoring with `model`
+    pub fn new(model: Arc<Model>) -> Self {
+        ScoringService { model }
+    }
+
+    /// Wrap the service into a server, to be added to a `tonic::transport::Server`
+    pub fn into_server(self) -> ScoringServer<Self> {
+        ScoringServer::new(self)
+    }
+
+    /// Score documents on a blocking thread so large batches don't stall the runtime
+    async fn predict_documents(&self, documents: Vec<Document>) -> Result<Vec<f64>, Status> {
+        let model = self.model.clone();
+        tokio::task::spawn_blocking(move || {
+            let (float_features, cat_features): (Vec<_>, Vec<_>) = documents
+                .into_iter()
+                .map(|document| (document.float_features, document.cat_features))
+                .unzip();
+            let dimensions = model.prediction_dimensions(model.get_prediction_type());
+            model.calc_prediction(&float_features, &cat_features, dimensions)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("prediction task failed: {}", e)))?
+        .map_err(to_status)
+    }
+}
+
+#[tonic::async_trait]
+impl Scoring for ScoringService {
+    async fn predict(
+        &self,
+        request: Request<PredictRequest>,
+    ) -> Result<Response<PredictResponse>, Status> {
+        let document = request
+            .into_inner()
+            .document
+            .ok_or_else(|| Status::invalid_argument("request has no document"))?;
+        let predictions = self.predict_documents(vec![document]).await?;
+        Ok(Response::new(PredictResponse { predictions }))
+    }
+
+    async fn predict_batch(
+        &self,
+        request: Request<PredictBatchRequest>,
+    ) -> Result<Response<PredictBatchResponse>, Status> {
+        let predictions = self
+            .predict_documents(request.into_inner().documents)
+            .await?;
+        Ok(Response::new(PredictBatchResponse {
+            predictions,
+            dimensions: self
+                .model
+                .prediction_dimensions(self.model.get_prediction_type())
+                as u64,
+        }))
+    }
+
+    async fn model_info(
+        &self,
+        _request: Request<ModelInfoRequest>,
+    ) -> Result<Response<ModelInfoResponse>, Status> {
+        let model = &self.model;
+        let (float_feature_names, cat_feature_names) =
+            model.get_feature_layout().map_err(to_status)?;
+        Ok(Response::new(ModelInfoResponse {
+            tree_count: model.get_tree_count() as u64,
+            dimensions: model.prediction_dimensions(model.get_prediction_type()) as u64,
+            float_features_count: model.get_float_features_count() as u64,
+            cat_features_count: model.get_cat_features_count() as u64,
+            text_features_count: model.get_text_features_count() as u64,
+            embedding_features_count: model.get_embedding_features_count() as u64,
+            float_feature_names,
+            cat_feature_names,
+            loss_function: model.loss_function(),
+        }))
+    }
+}
+
+fn to_status(error: CatBoostError) -> Status {
+    match error {
+        CatBoostError::InvalidInput { .. } | CatBoostError::InvalidArgument(_) => {
+            Status::invalid_argument(error.to_string())
+        }
+        _ => Status::internal(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scoring_service() {
+        let service = ScoringService::new(Arc::new(Model::load("files/model.bin").unwrap()));
+        let document = Document {
+            float_features: vec![-10.0, 5.0, 753.0],
+            cat_features: vec![String::from("north")],
+        };
+
+        let response = service
+            .predict(Request::new(PredictRequest {
+                document: Some(document.clone()),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(response.into_inner().predictions, vec![0.9980003729960197]);
+
+        let response = service
+            .predict_batch(Request::new(PredictBatchRequest {
+                documents: vec![document.clone(), document],
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.predictions.len(), 2);
+        assert_eq!(response.dimensions, 1);
+
+        let status = service
+            .predict(Request::new(PredictRequest { document: None }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let info = service
+            .model_info(Request::new(ModelInfoRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(info.tree_count, 1000);
+        assert_eq!(info.float_feature_names.len(), 3);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+/// Hash a string categorical feature value the same way CatBoost does internally
+pub fn hash_cat_feature(cat_feature: &str) -> i32 {
+    unsafe {
+        catboost_sys::GetStringCatFeatureHash(
+            cat_feature.as_ptr() as *const std::os::raw::c_char,
+            cat_feature.len(),
+        )
+    }
+}
+
+/// Hash a batch of string categorical feature values
+pub fn hash_cat_features<S: AsRef<str>>(cat_features: &[S]) -> Vec<i32> {
+    cat_features
+        .iter()
+        .map(|cat_feature| hash_cat_feature(cat_feature.as_ref()))
+        .collect()
+}
+
+/// Hash an integer categorical feature value, equivalent to hashing its decimal string form
+pub fn hash_integer_cat_feature(cat_feature: i64) -> i32 {
+    unsafe { catboost_sys::GetIntegerCatFeatureHash(cat_feature) }
+}
+
+/// Cache of categorical feature hashes for services where the same values repeat across calls.
+/// Recently used values are kept in two generations of at most half the capacity each: once
+/// the current generation is full it becomes the previous one, and values not looked up since
+/// are dropped. The cache never holds more values than its capacity. Hashes can be passed to
+/// `Model::calc_model_prediction_hashed`. The cache can be shared between threads.
+#[derive(Debug)]
+pub struct CatHasher {
+    capacity: usize,
+    generations: Mutex<Generations>,
+}
+
+#[derive(Debug, Default)]
+struct Generations {
+    current: HashMap<String, i32>,
+    previous: HashMap<String, i32>,
+}
+
+impl Generations {
+    fn hash(&mut self, cat_feature: &str, capacity: usize) -> i32 {
+        if let Some(hash) = self.current.get(cat_feature) {
+            return *hash;
+        }
+        let hash = match self.previous.remove(cat_feature) {
+            Some(hash) => hash,
+            None => hash_cat_feature(cat_feature),
+        };
+        if self.current.len() >= (capacity / 2).max(1) {
+            self.previous = std::mem::take(&mut self.current);
+        }
+        // Both generations only fit from a capacity of 2, below that a single value is kept
+        if self.current.len() + self.previous.len() >= capacity {
+            self.previous.clear();
+        }
+        self.current.insert(cat_feature.to_owned(), hash);
+        hash
+    }
+}
+
+impl CatHasher {
+    /// Create a cache holding at most `capacity` values, and at least one
+    pub fn new(capacity: usize) -> Self {
+        CatHasher {
+            capacity: capacity.max(1),
+            generations: Mutex::default(),
+        }
+    }
+
+    /// Hash a string categorical feature value, reusing the cached hash when present
+    pub fn hash(&self, cat_feature: &str) -> i32 {
+        self.lock().hash(cat_feature, self.capacity)
+    }
+
+    /// Hash a batch of string categorical feature values, locking the cache once
+    pub fn hash_batch<S: AsRef<str>>(&self, cat_features: &[S]) -> Vec<i32> {
+        let mut generations = self.lock();
+        cat_features
+            .iter()
+            .map(|cat_feature| generations.hash(cat_feature.as_ref(), self.capacity))
+            .collect()
+    }
+
+    /// Get the number of cached values
+    pub fn len(&self) -> usize {
+        let generations = self.lock();
+        generations.current.len() + generations.previous.len()
+    }
+
+    /// Check whether no values are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all cached values
+    pub fn clear(&self) {
+        *self.lock() = Generations::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Generations> {
+        // The cache only holds plain values, so it stays consistent after a panic
+        self.generations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_batch_matches_single_values() {
+        let hashes = hash_cat_features(&["north", "south"]);
+        assert_eq!(
+            hashes,
+            vec![hash_cat_feature("north"), hash_cat_feature("south")]
+        );
+        assert_ne!(hashes[0], hashes[1]);
+    }
+
+    #[test]
+    fn cat_hasher_is_bounded() {
+        let hasher = CatHasher::new(4);
+        assert!(hasher.is_empty());
+        assert_eq!(
+            hasher.hash_batch(&["north", "south", "north"]),
+            vec![
+                hash_cat_feature("north"),
+                hash_cat_feature("south"),
+                hash_cat_feature("north")
+            ]
+        );
+        assert_eq!(hasher.len(), 2);
+
+        for value in ["east", "west", "up", "down"] {
+            hasher.hash(value);
+        }
+        assert!(hasher.len() <= 4);
+        assert_eq!(hasher.hash("north"), hash_cat_feature("north"));
+        hasher.clear();
+        assert!(hasher.is_empty());
+    }
+
+    #[test]
+    fn cat_hasher_with_capacity_one() {
+        for capacity in [0, 1] {
+            let hasher = CatHasher::new(capacity);
+            for value in ["north", "south", "north", "east"] {
+                assert_eq!(hasher.hash(value), hash_cat_feature(value));
+                assert_eq!(hasher.len(), 1);
+            }
+        }
+
+        let hasher = CatHasher::new(3);
+        hasher.hash_batch(&["a", "b", "c", "d", "e", "a"]);
+        assert!(hasher.len() <= 3);
+    }
+
+    #[test]
+    fn hash_integer_matches_string_form() {
+        assert_eq!(hash_integer_cat_feature(42), hash_cat_feature("42"));
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+/// How the keys of `/predict` request rows are mapped to model features
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestSchema {
+    /// Match keys against the feature names stored in the model
+    ByName,
+    /// Read float and categorical features from the given keys, in model order
+    Keys {
+        float_keys: Vec<String>,
+        cat_keys: Vec<String>,
+    },
+}
+
+impl RequestSchema {
+    /// Keys of the float and categorical features of `model`, in model order
+    fn resolve(&self, model: &Model) -> CatBoostResult<(Vec<String>, Vec<String>)> {
+        match self {
+            RequestSchema::ByName => model.get_feature_layout(),
+            RequestSchema::Keys {
+                float_keys,
+                cat_keys,
+            } => {
+                check_key_count(float_keys, model.get_float_features_count(), "float")?;
+                check_key_count(cat_keys, model.get_cat_features_count(), "categorical")?;
+                Ok((float_keys.clone(), cat_keys.clone()))
+            }
+        }
+    }
+}
+
+fn check_key_count(keys: &[String], expected: usize, kind: &str) -> CatBoostResult<()> {
+    if keys.len() != expected {
+        return Err(CatBoostError::invalid_input(
+            format!("request schema has {} {} keys", keys.len(), kind),
+            expected,
+            keys.len(),
+        ));
+    }
+    Ok(())
+}
+
+/// Model and feature keys shared by the request handlers
+struct ServerState {
+    model: Arc<Model>,
+    float_keys: Vec<String>,
+    cat_keys: Vec<String>,
+}
+
+/// Serve `model` over HTTP on `addr` until the server fails, reading request rows according to
+/// `schema`, see `router` for the endpoints
+pub async fn serve<A: ToSocketAddrs>(
+    model: Model,
+    addr: A,
+    schema: RequestSchema,
+) -> CatBoostResult<()> {
+    let routes = router(Arc::new(model), &schema)?;
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| CatBoostError::Io(format!("failed to bind HTTP server: {}", e)))?;
+    axum::serve(listener, routes)
+        .await
+        .map_err(|e| CatBoostError::Io(format!("HTTP server failed: {}", e)))
+}
+
+/// Build the routes of the scoring server, to be served directly or nested into an existing
+/// application. Fails when `schema` does not give one key per model feature.
+/// - `POST /predict` takes `{"rows": [...]}` with one JSON object per document, holding the
+///   features under the keys of `schema` as for `Model::predict_json`, and answers
+///   `{"predictions": [...]}` with one raw value per document, or `{"error": "..."}` with status
+///   400 for invalid input and 500 when scoring fails
+/// - `GET /health` answers `{"status": "ok"}`
+pub fn router(model: Arc<Model>, schema: &RequestSchema) -> CatBoostResult<Router> {
+    let (float_keys, cat_keys) = schema.resolve(&model)?;
+    let state = ServerState {
+        model,
+        float_keys,
+        cat_keys,
+    };
+    Ok(Router::new()
+        .route("/predict", post(predict))
+        .route("/health", get(health))
+        .with_state(Arc::new(state)))
+}
+
+async fn predict(
+    State(state): State<Arc<ServerState>>,
+    Json(body): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    let rows = match body.get("rows").and_then(Value::as_array) {
+        Some(rows) => rows.clone(),
+        None => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "expected a JSON object with a rows array",
+            )
+        }
+    };
+
+    // Score on a blocking thread so large batches don't stall the server
+    let predictions = tokio::task::spawn_blocking(move || {
+        state
+            .model
+            .predict_json_keys(&rows, &state.float_keys, &state.cat_keys)
+    });
+    match predictions.await {
+        Ok(Ok(predictions)) => (StatusCode::OK, Json(json!({ "predictions": predictions }))),
+        Ok(Err(e)) => error_response(error_status(&e), &e.to_string()),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &format!("prediction task failed: {}", e),
+        ),
+    }
+}
+
+async fn health() -> Json<Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+/// Status answered for a failed prediction, blaming the client only for input it can fix
+fn error_status(error: &CatBoostError) -> StatusCode {
+    match error {
+        CatBoostError::InvalidInput { .. } | CatBoostError::InvalidArgument(_) => {
+            StatusCode::BAD_REQUEST
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> (StatusCode, Json<Value>) {
+    (status, Json(json!({ "error": message })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_state(model: Arc<Model>, schema: &RequestSchema) -> Arc<ServerState> {
+        let (float_keys, cat_keys) = schema.resolve(&model).unwrap();
+        Arc::new(ServerState {
+            model,
+            float_keys,
+            cat_keys,
+        })
+    }
+
+    #[tokio::test]
+    async fn predict_over_http() {
+        let model = Arc::new(Model::load("files/model.bin").unwrap());
+        let state = server_state(model.clone(), &RequestSchema::ByName);
+        let (float_feature_names, cat_feature_names) = model.get_feature_layout().unwrap();
+        let mut row = serde_json::Map::new();
+        for (name, value) in float_feature_names.iter().zip([-10.0, 5.0, 753.0]) {
+            row.insert(name.clone(), json!(value));
+        }
+        row.insert(cat_feature_names[0].clone(), json!("north"));
+
+        let (status, Json(response)) =
+            predict(State(state.clone()), Json(json!({ "rows": [row] }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["predictions"][0], json!(0.9980003729960197));
+
+        let (status, Json(response)) =
+            predict(State(state.clone()), Json(json!({ "rows": [{}] }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(response["error"].is_string());
+
+        let (status, _) = predict(State(state), Json(json!([]))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(health().await.0, json!({ "status": "ok" }));
+    }
+
+    #[tokio::test]
+    async fn predict_with_request_keys() {
+        let model = Arc::new(Model::load("files/model.bin").unwrap());
+        let schema = RequestSchema::Keys {
+            float_keys: vec!["a".into(), "b".into(), "c".into()],
+            cat_keys: vec!["region".into()],
+        };
+        let state = server_state(model.clone(), &schema);
+        let row = json!({ "a": -10.0, "b": 5.0, "c": 753.0, "region": "north" });
+        let (status, Json(response)) = predict(State(state), Json(json!({ "rows": [row] }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["predictions"][0], json!(0.9980003729960197));
+
+        let schema = RequestSchema::Keys {
+            float_keys: vec!["a".into()],
+            cat_keys: vec!["region".into()],
+        };
+        assert!(matches!(
+            router(model, &schema),
+            Err(CatBoostError::InvalidInput {
+                expected: 3,
+                got: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn error_statuses() {
+        assert_eq!(
+            error_status(&CatBoostError::invalid_input("too few features", 3, 2)),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            error_status(&CatBoostError::InvalidArgument("missing feature".into())),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            error_status(&CatBoostError::NativeError("evaluation failed".into())),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+use serde_json::Value;
+
+impl Model {
+    /// Calculate raw model predictions on JSON objects, extracting features by name. Float
+    /// features must be numbers, or null for missing values. Categorical features may be strings,
+    /// numbers or booleans, which are converted to their string form.
+    pub fn predict_json(&self, rows: &[Value]) -> CatBoostResult<Vec<f64>> {
+        let (float_feature_names, cat_feature_names) = self.get_feature_layout()?;
+        self.predict_json_keys(rows, &float_feature_names, &cat_feature_names)
+    }
+
+    /// Calculate raw model predictions on JSON objects as `predict_json` does, reading the float
+    /// and categorical features from the given keys, in model order
+    pub(crate) fn predict_json_keys(
+        &self,
+        rows: &[Value],
+        float_feature_names: &[String],
+        cat_feature_names: &[String],
+    ) -> CatBoostResult<Vec<f64>> {
+        let mut float_features = Vec::with_capacity(rows.len());
+        let mut cat_features = Vec::with_capacity(rows.len());
+        for (row_index, row) in rows.iter().enumerate() {
+            let row = row.as_object().ok_or_else(|| {
+                CatBoostError::InvalidArgument(format!("row {}: expected a JSON object", row_index))
+            })?;
+
+            let doc_float_features = float_feature_names
+                .iter()
+                .map(|name| match row.get(name) {
+                    Some(Value::Number(value)) => Ok(value.as_f64().unwrap_or(f64::NAN) as f32),
+                    Some(Value::Null) => Ok(f32::NAN),
+                    Some(_) => Err(CatBoostError::InvalidArgument(format!(
+                        "row {}: expected a number for feature {}",
+                        row_index, name
+                    ))),
+                    None => Err(missing_feature(row_index, name)),
+                })
+                .collect::<CatBoostResult<Vec<_>>>()?;
+            let doc_cat_features = cat_feature_names
+                .iter()
+                .map(|name| match row.get(name) {
+                    Some(Value::String(value)) => Ok(value.clone()),
+                    Some(Value::Number(value)) => Ok(value.to_string()),
+                    Some(Value::Bool(value)) => Ok(value.to_string()),
+                    Some(_) => Err(CatBoostError::InvalidArgument(format!(
+                        "row {}: expected a string for feature {}",
+                        row_index, name
+                    ))),
+                    None => Err(missing_feature(row_index, name)),
+                })
+                .collect::<CatBoostResult<Vec<_>>>()?;
+
+            float_features.push(doc_float_features);
+            cat_features.push(doc_cat_features);
+        }
+
+        self.calc_prediction(
+            &float_features,
+            &cat_features,
+            self.prediction_dimensions(self.get_prediction_type()),
+        )
+    }
+}
+
+fn missing_feature(row_index: usize, name: &str) -> CatBoostError {
+    CatBoostError::InvalidArgument(format!("row {}: missing feature {}", row_index, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predict_json() {
+        let model = Model::load("files/model.bin").unwrap();
+        let (float_feature_names, cat_feature_names) = model.get_feature_layout().unwrap();
+        let row = format!(
+            r#"{{"{}": -10.0, "{}": 5.0, "{}": 753, "{}": "north"}}"#,
+            float_feature_names[0],
+            float_feature_names[1],
+            float_feature_names[2],
+            cat_feature_names[0]
+        );
+        let rows = vec![serde_json::from_str(&row).unwrap()];
+        let prediction = model.predict_json(&rows).unwrap();
+        assert_eq!(prediction[0], 0.9980003729960197);
+
+        let rows = vec![serde_json::from_str("{}").unwrap()];
+        assert!(model.predict_json(&rows).is_err());
+    }
+}
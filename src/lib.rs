@@ -1,8 +1,104 @@
+mod batch;
+pub use crate::batch::{PredictionBatch, PredictionBatchBuilder};
+
+pub mod calibration;
+
+pub mod compare;
+
+mod context;
+pub use crate::context::PredictionContext;
+
+pub mod drift;
+
 mod error;
 pub use crate::error::{CatBoostError, CatBoostResult};
 
+mod evaluator_type;
+pub use crate::evaluator_type::EvaluatorType;
+
+mod feature;
+pub use crate::feature::{FeatureValue, Row};
+
+mod hash;
+pub use crate::hash::{
+    hash_cat_feature, hash_cat_features, hash_integer_cat_feature, CatHasher,
+};
+
+mod metric;
+pub use crate::metric::Metric;
+
 mod model;
-pub use crate::model::Model;
+pub use crate::model::{ClassPrediction, Model};
+
+mod model_pool;
+pub use crate::model_pool::ModelPool;
+
+mod record;
+pub use crate::record::ScoreRecord;
+#[cfg(feature = "derive")]
+pub use catboost_derive::ScoreRecord;
+// Lets code generated by the derive macro refer to `::catboost_rs` inside the crate's own tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as catboost_rs;
+
+mod observer;
+pub use crate::observer::{Observer, PredictionEvent};
+
+mod pool;
+pub use crate::pool::{ColumnType, Pool, PoolBuilder};
+
+mod prediction;
+pub use crate::prediction::{ClassProbability, MultiTargetPrediction};
+
+mod prediction_builder;
+pub use crate::prediction_builder::PredictionBuilder;
+
+mod prediction_type;
+pub use crate::prediction_type::PredictionType;
+
+mod predictor;
+pub use crate::predictor::Predictor;
+
+mod registry;
+pub use crate::registry::ModelRegistry;
+
+mod stream;
+pub use crate::stream::PredictionIter;
+
+#[cfg(feature = "ndarray")]
+mod array;
+
+#[cfg(feature = "arrow")]
+mod record_batch;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+#[cfg(feature = "async")]
+mod async_model;
+
+#[cfg(feature = "serde")]
+mod json;
+#[cfg(feature = "serde")]
+mod serde_model;
+
+#[cfg(feature = "csv")]
+mod csv_scoring;
+#[cfg(feature = "csv")]
+pub use crate::csv_scoring::{CsvColumns, CsvOptions};
+
+#[cfg(feature = "notify")]
+mod reloading;
+#[cfg(feature = "notify")]
+pub use crate::reloading::ReloadingModel;
+
+#[cfg(feature = "http-server")]
+mod http_server;
+#[cfg(feature = "http-server")]
+pub use crate::http_server::{router, serve, RequestSchema};
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,227 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::argmax;
+
+/// Metric computed by `Model::eval_metrics` from raw predictions. The C API has no metric
+/// evaluation, so these are implemented in Rust after the usual definitions rather than by
+/// CatBoost itself. Documents are weighted by the pool weights when present.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Metric {
+    /// Root mean squared error of the raw predictions
+    RMSE,
+    /// Mean absolute error of the raw predictions
+    MAE,
+    /// Binary log loss, with labels above 0.5 treated as the positive class
+    Logloss,
+    /// Binary cross entropy against labels given as probabilities of the positive class
+    CrossEntropy,
+    /// Multiclass log loss of the softmax of the raw predictions, labels are class indices
+    MultiClass,
+    /// Share of correctly classified documents, for binary and multiclass models
+    Accuracy,
+    /// Area under the ROC curve of a binary classifier
+    AUC,
+}
+
+impl Metric {
+    /// Name of the metric as used in CatBoost parameters and reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Metric::RMSE => "RMSE",
+            Metric::MAE => "MAE",
+            Metric::Logloss => "Logloss",
+            Metric::CrossEntropy => "CrossEntropy",
+            Metric::MultiClass => "MultiClass",
+            Metric::Accuracy => "Accuracy",
+            Metric::AUC => "AUC",
+        }
+    }
+
+    /// Compute the metric on raw predictions holding `dimensions` values per document
+    pub(crate) fn eval(
+        &self,
+        raw_predictions: &[f64],
+        dimensions: usize,
+        labels: &[f32],
+        weights: Option<&[f32]>,
+    ) -> CatBoostResult<f64> {
+        let single_dimension = matches!(
+            self,
+            Metric::RMSE | Metric::MAE | Metric::Logloss | Metric::CrossEntropy | Metric::AUC
+        );
+        if single_dimension && dimensions != 1 {
+            return Err(CatBoostError::InvalidArgument(format!(
+                "metric {} needs a model with one dimension, got {}",
+                self.as_str(),
+                dimensions
+            )));
+        }
+
+        let weight = |doc: usize| weights.map_or(1.0, |weights| f64::from(weights[doc]));
+        let weighted_mean = |loss: &dyn Fn(usize, &[f64]) -> CatBoostResult<f64>| {
+            let mut loss_sum = 0.0;
+            let mut weight_sum = 0.0;
+            for (doc, doc_prediction) in raw_predictions.chunks(dimensions).enumerate() {
+                loss_sum += weight(doc) * loss(doc, doc_prediction)?;
+                weight_sum += weight(doc);
+            }
+            Ok(loss_sum / weight_sum)
+        };
+
+        match self {
+            Metric::RMSE => Ok(weighted_mean(&|doc, prediction| {
+                Ok((prediction[0] - f64::from(labels[doc])).powi(2))
+            })?
+            .sqrt()),
+            Metric::MAE => {
+                weighted_mean(&|doc, prediction| Ok((prediction[0] - f64::from(labels[doc])).abs()))
+            }
+            Metric::Logloss => weighted_mean(&|doc, prediction| {
+                let target = if labels[doc] > 0.5 { 1.0 } else { 0.0 };
+                Ok(log_loss(prediction[0], target))
+            }),
+            Metric::CrossEntropy => weighted_mean(&|doc, prediction| {
+                Ok(log_loss(prediction[0], f64::from(labels[doc])))
+            }),
+            Metric::MultiClass => weighted_mean(&|doc, prediction| {
+                let class = class_index(labels[doc], dimensions)?;
+                let max = prediction.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                let log_sum_exp =
+                    max + prediction.iter().map(|x| (x - max).exp()).sum::<f64>().ln();
+                Ok(log_sum_exp - prediction[class])
+            }),
+            Metric::Accuracy => weighted_mean(&|doc, prediction| {
+                let correct = if dimensions == 1 {
+                    (prediction[0] > 0.0) == (labels[doc] > 0.5)
+                } else {
+                    argmax(prediction) == class_index(labels[doc], dimensions)?
+                };
+                Ok(if correct { 1.0 } else { 0.0 })
+            }),
+            Metric::AUC => auc(raw_predictions, labels, weights),
+        }
+    }
+}
+
+/// Log loss of a raw formula value against a target probability, computed without forming the
+/// sigmoid so large raw values do not lose precision
+fn log_loss(raw_prediction: f64, target: f64) -> f64 {
+    let softplus = if raw_prediction > 0.0 {
+        raw_prediction + (-raw_prediction).exp().ln_1p()
+    } else {
+        raw_prediction.exp().ln_1p()
+    };
+    softplus - target * raw_prediction
+}
+
+fn class_index(label: f32, dimensions: usize) -> CatBoostResult<usize> {
+    if label < 0.0 || label.fract() != 0.0 || label as usize >= dimensions {
+        return Err(CatBoostError::InvalidArgument(format!(
+            "label {} is not a class index of a model with {} classes",
+            label, dimensions
+        )));
+    }
+    Ok(label as usize)
+}
+
+/// Weighted probability that a positive document is ranked above a negative one, counting ties
+/// as half
+fn auc(raw_predictions: &[f64], labels: &[f32], weights: Option<&[f32]>) -> CatBoostResult<f64> {
+    let weight = |doc: usize| weights.map_or(1.0, |weights| f64::from(weights[doc]));
+    let mut docs = (0..raw_predictions.len()).collect::<Vec<_>>();
+    docs.sort_by(|a, b| raw_predictions[*a].total_cmp(&raw_predictions[*b]));
+
+    let mut area = 0.0;
+    let mut negative_below = 0.0;
+    let mut positive_total = 0.0;
+    for ties in docs.chunk_by(|a, b| raw_predictions[*a] == raw_predictions[*b]) {
+        let (mut positive, mut negative) = (0.0, 0.0);
+        for doc in ties {
+            if labels[*doc] > 0.5 {
+                positive += weight(*doc);
+            } else {
+                negative += weight(*doc);
+            }
+        }
+        area += positive * (negative_below + 0.5 * negative);
+        negative_below += negative;
+        positive_total += positive;
+    }
+
+    if positive_total == 0.0 || negative_below == 0.0 {
+        return Err(CatBoostError::InvalidArgument(
+            "AUC is undefined when all documents belong to one class".into(),
+        ));
+    }
+    Ok(area / (positive_total * negative_below))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regression_metrics() {
+        let predictions = [1.0, 2.0, 4.0];
+        let labels = [1.0, 3.0, 2.0];
+
+        let rmse = Metric::RMSE.eval(&predictions, 1, &labels, None).unwrap();
+        assert!((rmse - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        let mae = Metric::MAE
+            .eval(&predictions, 1, &labels, Some(&[1.0, 1.0, 2.0]))
+            .unwrap();
+        assert!((mae - 5.0 / 4.0).abs() < 1e-12);
+        assert!(Metric::RMSE
+            .eval(&predictions, 3, &labels[..1], None)
+            .is_err());
+    }
+
+    #[test]
+    fn classification_metrics() {
+        let predictions = [2.0, -1.0, 0.5, -3.0];
+        let labels = [1.0, 0.0, 0.0, 1.0];
+
+        let logloss = Metric::Logloss
+            .eval(&predictions, 1, &labels, None)
+            .unwrap();
+        let expected = [(2.0f64, 1.0f64), (-1.0, 0.0), (0.5, 0.0), (-3.0, 1.0)]
+            .iter()
+            .map(|(raw, target)| {
+                let p = 1. / (1. + (-raw).exp());
+                -(target * p.ln() + (1. - target) * (1. - p).ln())
+            })
+            .sum::<f64>()
+            / 4.0;
+        assert!((logloss - expected).abs() < 1e-12);
+        assert_eq!(
+            Metric::Accuracy
+                .eval(&predictions, 1, &labels, None)
+                .unwrap(),
+            0.5
+        );
+        assert_eq!(
+            Metric::AUC.eval(&predictions, 1, &labels, None).unwrap(),
+            0.5
+        );
+        assert!(Metric::AUC.eval(&predictions, 1, &[1.0; 4], None).is_err());
+    }
+
+    #[test]
+    fn multiclass_metrics() {
+        let predictions = [0.0, 2.0, 1.0, 1.0, 0.0, 0.0];
+        let labels = [1.0, 2.0];
+
+        assert_eq!(
+            Metric::Accuracy
+                .eval(&predictions, 3, &labels, None)
+                .unwrap(),
+            0.5
+        );
+        let multiclass = Metric::MultiClass
+            .eval(&predictions, 3, &labels, None)
+            .unwrap();
+        assert!(multiclass > 0.0);
+        assert!(Metric::MultiClass
+            .eval(&predictions, 3, &[0.0, 3.0], None)
+            .is_err());
+    }
+}
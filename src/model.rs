@@ -1,84 +1,561 @@
 use crate::error::{CatBoostError, CatBoostResult};
+use crate::evaluator_type::EvaluatorType;
+use crate::feature::{FeatureKind, FeatureValue};
+use crate::hash::{hash_cat_feature, hash_cat_features};
+use crate::metric::Metric;
+use crate::observer::{Observer, PredictionEvent};
+use crate::pool::Pool;
+use crate::prediction::{ClassProbability, MultiTargetPrediction};
+use crate::prediction_type::PredictionType;
 use catboost_sys;
-use std::ffi::CString;
-use std::os::unix::ffi::OsStrExt;
+use smallvec::SmallVec;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::io::Read;
+use std::ops::Range;
 use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Instant;
+
+/// Metadata keys CatBoost writes when training a model
+const KNOWN_METADATA_KEYS: &[&str] = &[
+    "params",
+    "catboost_version_info",
+    "model_guid",
+    "train_finish_time",
+    "training",
+    "output_options",
+    "class_params",
+    "multiclass_params",
+    "binclass_probability_threshold",
+];
+
+/// Number of leading bytes of a model file read to describe its format when loading fails
+const MODEL_HEADER_SIZE: u64 = 4096;
+
+/// Number of documents up to which per-call pointer arrays are kept on the stack
+const SMALL_BATCH_SIZE: usize = 16;
+
+/// Array of per-document row pointers handed to the native evaluator, allocated on the heap
+/// only for batches of more than `SMALL_BATCH_SIZE` documents
+pub(crate) type PointerArray<T> = SmallVec<[*const T; SMALL_BATCH_SIZE]>;
+
+/// Loss functions of classification models
+const CLASSIFICATION_LOSS_FUNCTIONS: &[&str] = &[
+    "Logloss",
+    "CrossEntropy",
+    "MultiClass",
+    "MultiClassOneVsAll",
+];
+
+/// Loss functions of ranking models
+const RANKING_LOSS_FUNCTIONS: &[&str] = &[
+    "YetiRank",
+    "YetiRankPairwise",
+    "PairLogit",
+    "PairLogitPairwise",
+    "QueryRMSE",
+    "QuerySoftMax",
+    "QueryCrossEntropy",
+    "StochasticFilter",
+    "StochasticRank",
+    "LambdaMart",
+];
 
 pub struct Model {
     handle: *mut catboost_sys::ModelCalcerHandle,
+    prediction_type: PredictionType,
+    evaluator_type: EvaluatorType,
+    /// Serialized model the handle was loaded from, when it was kept so the model can be saved
+    /// and cloned
+    model_bytes: Option<Vec<u8>>,
+    /// Size of the serialized model in bytes
+    model_size: usize,
+    observer: Option<Arc<dyn Observer>>,
+    /// Shared by calls evaluating with the model's prediction type, held exclusively by calls
+    /// that switch the native handle to another prediction type
+    handle_lock: RwLock<()>,
 }
 
 impl Model {
-    fn new() -> Self {
+    /// Create an empty model handle, failing when CatBoost cannot allocate one
+    fn new(model_bytes: Option<Vec<u8>>, model_size: usize) -> CatBoostResult<Self> {
         let model_handle = unsafe { catboost_sys::ModelCalcerCreate() };
-        Model {
-            handle: model_handle,
+        if model_handle.is_null() {
+            return Err(CatBoostError::ModelLoad(
+                "failed to create a CatBoost model handle".into(),
+            ));
         }
+        Ok(Model {
+            handle: model_handle,
+            prediction_type: PredictionType::RawFormulaVal,
+            evaluator_type: EvaluatorType::Cpu,
+            model_bytes,
+            model_size,
+            observer: None,
+            handle_lock: RwLock::new(()),
+        })
     }
 
-    /// Load a model from a file
+    /// Load a model from a file in CatBoost binary (cbm) format. The serialized model is not
+    /// kept in memory, load it with `load_buffer_retained` instead to save or clone the model
+    /// later.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "catboost.load",
+            skip_all,
+            fields(path = %path.as_ref().display())
+        )
+    )]
     pub fn load<P: AsRef<Path>>(path: P) -> CatBoostResult<Self> {
-        let model = Model::new();
-        let path_c_str = CString::new(path.as_ref().as_os_str().as_bytes()).unwrap();
+        let path = path.as_ref();
+        CatBoostError::check_path(path)?;
+        let path_c_str = path_to_c_string(path)?;
+        let model_size = std::fs::metadata(path).map_or(0, |metadata| metadata.len() as usize);
+        let model = Model::new(None, model_size)?;
         CatBoostError::check_return_value(unsafe {
             catboost_sys::LoadFullModelFromFile(model.handle, path_c_str.as_ptr())
-        })?;
+        })
+        .map_err(|e| load_error(e, read_model_header(path).as_deref(), model_size))?;
         Ok(model)
     }
 
-    /// Load a model from a buffer
-    pub fn load_buffer<P: AsRef<Vec<u8>>>(buffer: P) -> CatBoostResult<Self> {
-        let model = Model::new();
+    /// Load a model from a buffer holding a model in CatBoost binary (cbm) format, such as a
+    /// `Vec<u8>`, a byte slice or a memory-mapped file. The buffer is neither copied nor kept,
+    /// use `load_buffer_retained` to save or clone the model later.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "catboost.load_buffer",
+            skip_all,
+            fields(bytes = buffer.as_ref().len())
+        )
+    )]
+    pub fn load_buffer<P: AsRef<[u8]>>(buffer: P) -> CatBoostResult<Self> {
+        let buffer = buffer.as_ref();
+        let model = Model::new(None, buffer.len())?;
         CatBoostError::check_return_value(unsafe {
             catboost_sys::LoadFullModelFromBuffer(
                 model.handle,
-                buffer.as_ref().as_ptr() as *const std::os::raw::c_void,
-                buffer.as_ref().len(),
+                buffer.as_ptr() as *const std::os::raw::c_void,
+                buffer.len(),
             )
-        })?;
+        })
+        .map_err(|e| load_error(e, Some(buffer), buffer.len()))?;
+        Ok(model)
+    }
+
+    /// Load a model from a buffer holding a model in CatBoost binary (cbm) format and keep the
+    /// buffer, so the model can be saved, serialized and cloned later
+    pub fn load_buffer_retained(buffer: Vec<u8>) -> CatBoostResult<Self> {
+        let mut model = Model::load_buffer(&buffer)?;
+        model.model_bytes = Some(buffer);
+        Ok(model)
+    }
+
+    /// Save the model to a file in CatBoost binary (cbm) format. Fails when the serialized model
+    /// was not kept at load time.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> CatBoostResult<()> {
+        CatBoostError::check_path(path.as_ref())?;
+        std::fs::write(path.as_ref(), self.as_bytes()?).map_err(|e| {
+            CatBoostError::Io(format!(
+                "failed to write model file {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })
+    }
+
+    /// Get the model serialized in CatBoost binary (cbm) format, as accepted by `load_buffer`.
+    /// Fails when the serialized model was not kept at load time.
+    pub fn to_bytes(&self) -> CatBoostResult<Vec<u8>> {
+        self.as_bytes().map(<[u8]>::to_vec)
+    }
+
+    /// Get the serialized model without copying it
+    pub(crate) fn as_bytes(&self) -> CatBoostResult<&[u8]> {
+        self.model_bytes.as_deref().ok_or_else(|| {
+            CatBoostError::InvalidArgument(
+                "the serialized model was not kept, load it with `Model::load_buffer_retained`"
+                    .into(),
+            )
+        })
+    }
+
+    /// Estimate the memory held by the model: the serialized model when it was kept at load
+    /// time, plus the native model. The C API has no memory usage query, so the native model is
+    /// estimated at the size of the serialized model it was loaded from, which stores the same
+    /// trees and feature tables.
+    pub fn used_memory_bytes(&self) -> usize {
+        let retained_bytes = self.model_bytes.as_ref().map_or(0, Vec::len);
+        retained_bytes + self.model_size
+    }
+
+    /// Copy the model by loading a separate handle from its serialized form. The copy keeps the
+    /// prediction type and observer but evaluates on the CPU, as the GPU device of the original
+    /// is not known. Fails for models loaded with `load` or `load_buffer`, which do not keep the
+    /// serialized model; load it with `load_buffer_retained` to copy it.
+    pub fn try_clone(&self) -> CatBoostResult<Self> {
+        let mut model = Model::load_buffer_retained(self.as_bytes()?.to_vec())?;
+        model.set_prediction_type(self.prediction_type)?;
+        model.observer = self.observer.clone();
         Ok(model)
     }
 
-    /// Calculate raw model predictions on float features and string categorical feature values
+    /// Calculate raw model predictions on float features and string categorical feature values.
+    /// Results hold as many values per document as the prediction type gives, see
+    /// `PredictionType`.
     pub fn calc_model_prediction(
         &self,
         float_features: Vec<Vec<f32>>,
         cat_features: Vec<Vec<String>>,
     ) -> CatBoostResult<Vec<f64>> {
-        let mut float_features_ptr = float_features
+        self.calc_prediction(
+            &float_features,
+            &cat_features,
+            self.prediction_dimensions(self.prediction_type),
+        )
+    }
+
+    /// Calculate raw model predictions on float features with missing values and string
+    /// categorical feature values. `None` values are passed to the model as NaN, which CatBoost
+    /// handles according to the `nan_mode` the model was trained with. An error is returned when
+    /// the model was trained with `nan_mode` set to `Forbidden` and missing values are present.
+    pub fn calc_model_prediction_with_missing(
+        &self,
+        float_features: Vec<Vec<Option<f32>>>,
+        cat_features: Vec<Vec<String>>,
+    ) -> CatBoostResult<Vec<f64>> {
+        let has_missing = float_features
             .iter()
-            .map(|x| x.as_ptr())
+            .any(|doc_float_features| doc_float_features.iter().any(Option::is_none));
+        if has_missing && self.get_nan_mode().as_deref() == Some("Forbidden") {
+            return Err(CatBoostError::InvalidArgument(
+                "model was trained with nan_mode Forbidden and does not accept missing values"
+                    .into(),
+            ));
+        }
+
+        let float_features = float_features
+            .into_iter()
+            .map(|doc_float_features| {
+                doc_float_features
+                    .into_iter()
+                    .map(|value| value.unwrap_or(f32::NAN))
+                    .collect::<Vec<_>>()
+            })
             .collect::<Vec<_>>();
+        self.calc_prediction(
+            &float_features,
+            &cat_features,
+            self.prediction_dimensions(self.prediction_type),
+        )
+    }
 
-        let hashed_cat_features = cat_features
+    /// Calculate raw model predictions on documents given as feature name to value maps. Feature
+    /// names are mapped to model feature positions using the names stored in the model, and an
+    /// error listing the missing features is returned when a document lacks any of them.
+    pub fn calc_model_prediction_by_name(
+        &self,
+        docs: &[HashMap<String, FeatureValue>],
+    ) -> CatBoostResult<Vec<f64>> {
+        let (float_feature_names, cat_feature_names) = self.get_feature_layout()?;
+
+        let mut float_features = Vec::with_capacity(docs.len());
+        let mut cat_features = Vec::with_capacity(docs.len());
+        for (doc_index, doc) in docs.iter().enumerate() {
+            let mut missing = Vec::new();
+            let mut doc_float_features = Vec::with_capacity(float_feature_names.len());
+            for name in &float_feature_names {
+                match doc.get(name) {
+                    Some(FeatureValue::Float(value)) => doc_float_features.push(*value),
+                    Some(FeatureValue::Missing) => doc_float_features.push(f32::NAN),
+                    Some(_) => {
+                        return Err(CatBoostError::InvalidArgument(format!(
+                            "document {}: expected a float value for feature {}",
+                            doc_index, name
+                        )))
+                    }
+                    None => missing.push(name.as_str()),
+                }
+            }
+            let mut doc_cat_features = Vec::with_capacity(cat_feature_names.len());
+            for name in &cat_feature_names {
+                match doc.get(name) {
+                    Some(FeatureValue::Cat(value)) => doc_cat_features.push(value.as_str()),
+                    Some(_) => {
+                        return Err(CatBoostError::InvalidArgument(format!(
+                            "document {}: expected a categorical value for feature {}",
+                            doc_index, name
+                        )))
+                    }
+                    None => missing.push(name.as_str()),
+                }
+            }
+            if !missing.is_empty() {
+                return Err(CatBoostError::InvalidArgument(format!(
+                    "document {}: missing features: {}",
+                    doc_index,
+                    missing.join(", ")
+                )));
+            }
+            float_features.push(doc_float_features);
+            cat_features.push(doc_cat_features);
+        }
+
+        self.calc_prediction(
+            &float_features,
+            &cat_features,
+            self.prediction_dimensions(self.prediction_type),
+        )
+    }
+
+    /// Calculate raw model predictions on borrowed float features and categorical feature values,
+    /// avoiding the need to copy inputs into owned vectors
+    pub fn calc_model_prediction_slices<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        self.calc_prediction(
+            float_features,
+            cat_features,
+            self.prediction_dimensions(self.prediction_type),
+        )
+    }
+
+    /// Calculate raw model predictions on borrowed `f64` float features and categorical feature
+    /// values. CatBoost evaluates models on `f32` values, so each value is rounded to the nearest
+    /// `f32`, and values beyond the `f32` range become infinite.
+    pub fn calc_model_prediction_f64<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        F: AsRef<[f64]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let float_features = float_features
             .iter()
-            .map(|doc_cat_features| {
-                doc_cat_features
+            .map(|doc_float_features| {
+                doc_float_features
+                    .as_ref()
                     .iter()
-                    .map(|cat_feature| unsafe {
-                        catboost_sys::GetStringCatFeatureHash(
-                            cat_feature.as_ptr() as *const std::os::raw::c_char,
-                            cat_feature.len(),
-                        )
-                    })
+                    .map(|value| *value as f32)
                     .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>();
+        self.calc_prediction(
+            &float_features,
+            cat_features,
+            self.prediction_dimensions(self.prediction_type),
+        )
+    }
 
-        let mut hashed_cat_features_ptr = hashed_cat_features
+    /// Calculate raw model predictions on borrowed float features and categorical feature values,
+    /// writing them into `out` instead of allocating a result vector. `out` must hold as many
+    /// values per document as the prediction type gives, see `PredictionType`.
+    pub fn predict_into<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+        out: &mut [f64],
+    ) -> CatBoostResult<()>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let expected = float_features.len() * self.prediction_dimensions(self.prediction_type);
+        if out.len() != expected {
+            return Err(CatBoostError::invalid_input(
+                format!(
+                    "output buffer has length {}, expected {}",
+                    out.len(),
+                    expected
+                ),
+                expected,
+                out.len(),
+            ));
+        }
+        self.check_input_shape(float_features, cat_features)?;
+
+        let float_features_ptr = float_features
+            .iter()
+            .map(|x| x.as_ref().as_ptr())
+            .collect::<PointerArray<_>>();
+        let hashed_cat_features = hash_docs_cat_features(cat_features);
+        self.calc_hashed_prediction_into(
+            float_features_ptr,
+            self.get_float_features_count(),
+            &hashed_cat_features,
+            self.prediction_type,
+            out,
+        )
+    }
+
+    /// Calculate raw model predictions on a very large batch, scoring `chunk_size` documents
+    /// per native call so that hashed categorical features and pointer arrays are only held for
+    /// one chunk at a time. Predictions are returned in input order; use `Model::predict_iter`
+    /// to stream them instead.
+    pub fn calc_model_prediction_chunked<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+        chunk_size: usize,
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        if chunk_size == 0 {
+            return Err(CatBoostError::InvalidArgument(
+                "chunk size must be greater than zero".into(),
+            ));
+        }
+        self.check_input_shape(float_features, cat_features)?;
+
+        let dimensions = self.prediction_dimensions(self.prediction_type);
+        let mut prediction = vec![0.0; float_features.len() * dimensions];
+        for ((float_chunk, cat_chunk), out) in float_features
+            .chunks(chunk_size)
+            .zip(cat_features.chunks(chunk_size))
+            .zip(prediction.chunks_mut(chunk_size * dimensions))
+        {
+            self.predict_into(float_chunk, cat_chunk, out)?;
+        }
+        Ok(prediction)
+    }
+
+    /// Calculate raw model predictions on float features and categorical features that were
+    /// already hashed, skipping the per-call hashing of string values
+    pub fn calc_model_prediction_hashed<F, H>(
+        &self,
+        float_features: &[F],
+        hashed_cat_features: &[H],
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        F: AsRef<[f32]>,
+        H: AsRef<[i32]>,
+    {
+        self.check_input_shape(float_features, hashed_cat_features)?;
+        let float_features_ptr = float_features
+            .iter()
+            .map(|x| x.as_ref().as_ptr())
+            .collect::<PointerArray<_>>();
+        self.calc_hashed_prediction(
+            float_features_ptr,
+            self.get_float_features_count(),
+            hashed_cat_features,
+            self.prediction_type,
+            self.prediction_dimensions(self.prediction_type),
+        )
+    }
+
+    /// Calculate raw model predictions on a contiguous row-major float feature matrix of the
+    /// given `(rows, cols)` shape, without copying the matrix into per-row vectors
+    pub fn calc_model_prediction_matrix<C, S>(
+        &self,
+        float_features: &[f32],
+        shape: (usize, usize),
+        cat_features: &[C],
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let (rows, cols) = shape;
+        if float_features.len() != rows * cols {
+            return Err(CatBoostError::invalid_input(
+                format!(
+                    "float feature matrix has {} values, expected {} for shape ({}, {})",
+                    float_features.len(),
+                    rows * cols,
+                    rows,
+                    cols
+                ),
+                rows * cols,
+                float_features.len(),
+            ));
+        }
+        self.check_matrix_shape(rows, cols, cat_features)?;
+
+        let float_features_ptr = (0..rows)
+            .map(|row| float_features[row * cols..].as_ptr())
+            .collect::<PointerArray<_>>();
+        self.calc_prediction_from_pointers(
+            float_features_ptr,
+            cols,
+            cat_features,
+            self.prediction_type,
+            self.prediction_dimensions(self.prediction_type),
+        )
+    }
+
+    /// Calculate raw model predictions for multiclass models, returning one vector of
+    /// per-dimension values for each document
+    pub fn calc_multiclass_prediction(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+    ) -> CatBoostResult<Vec<Vec<f64>>> {
+        Ok(self
+            .calc_multi_target_prediction(float_features, cat_features)?
+            .into_vec())
+    }
+
+    /// Calculate raw model predictions for models with several outputs per document, such as
+    /// MultiRMSE regression, reshaped using the number of values the prediction type gives per
+    /// document
+    pub fn calc_multi_target_prediction(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+    ) -> CatBoostResult<MultiTargetPrediction> {
+        let dimensions = self.prediction_dimensions(self.prediction_type);
+        let raw_results = self.calc_prediction(&float_features, &cat_features, dimensions)?;
+        Ok(MultiTargetPrediction::new(raw_results, dimensions))
+    }
+
+    /// Calculate raw model predictions on float features only, for models without categorical features
+    pub fn calc_model_prediction_flat(
+        &self,
+        float_features: Vec<Vec<f32>>,
+    ) -> CatBoostResult<Vec<f64>> {
+        check_feature_rows(
+            &float_features,
+            self.get_float_features_count(),
+            FeatureKind::Float,
+        )
+        .map_err(|e| self.with_schema(e, FeatureKind::Float))?;
+        if float_features.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut float_features_ptr = float_features
             .iter()
             .map(|x| x.as_ptr())
             .collect::<Vec<_>>();
 
-        let mut prediction = vec![0.0; float_features.len()];
-        CatBoostError::check_return_value(unsafe {
-            catboost_sys::CalcModelPredictionWithHashedCatFeatures(
+        let mut prediction =
+            vec![0.0; float_features.len() * self.prediction_dimensions(self.prediction_type)];
+        self.observe(self.prediction_type, float_features.len(), || unsafe {
+            catboost_sys::CalcModelPredictionFlat(
                 self.handle,
                 float_features.len(),
                 float_features_ptr.as_mut_ptr(),
                 float_features[0].len(),
-                hashed_cat_features_ptr.as_mut_ptr(),
-                cat_features[0].len(),
                 prediction.as_mut_ptr(),
                 prediction.len(),
             )
@@ -86,112 +563,2288 @@ impl Model {
         Ok(prediction)
     }
 
-    /// Apply sigmoid to get predict probability
-    // https://catboost.ai/en/docs/concepts/output-data_model-value-output#classification
-    pub fn calc_predict_proba(
+    /// Calculate raw model predictions using only the trees in `tree_start..tree_end`, e.g. to
+    /// compute staged predictions or evaluate a shrunk model. Results hold as many values per
+    /// document as the prediction type gives, see `PredictionType`.
+    pub fn calc_model_prediction_staged(
         &self,
         float_features: Vec<Vec<f32>>,
         cat_features: Vec<Vec<String>>,
+        tree_start: usize,
+        tree_end: usize,
     ) -> CatBoostResult<Vec<f64>> {
-        let raw_results = self.calc_model_prediction(float_features, cat_features)?;
-        let probabilities = raw_results.into_iter().map(sigmoid).collect();
-        Ok(probabilities)
+        self.calc_staged_prediction(
+            &float_features,
+            &cat_features,
+            tree_start..tree_end,
+            self.prediction_type,
+            self.prediction_dimensions(self.prediction_type),
+        )
     }
 
-    /// Get expected float feature count for model
-    pub fn get_float_features_count(&self) -> usize {
-        unsafe { catboost_sys::GetFloatFeaturesCount(self.handle) }
+    /// Calculate raw model predictions after every `step` trees, e.g. to plot a learning curve
+    /// or choose where to truncate the model. Element `i` holds the predictions of the first
+    /// `(i + 1) * step` trees, the last one those of all trees, with as many values per document
+    /// as the prediction type gives, see `PredictionType`. Each stage only evaluates its own trees
+    /// and adds their raw values to the previous stage, the model's prediction type is then
+    /// applied to the running sum.
+    pub fn staged_predictions<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+        step: usize,
+    ) -> CatBoostResult<Vec<Vec<f64>>>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        if step == 0 {
+            return Err(CatBoostError::InvalidArgument(
+                "step must be greater than zero".into(),
+            ));
+        }
+        let tree_count = self.get_tree_count();
+        let dimensions = self.prediction_dimensions(PredictionType::RawFormulaVal);
+        let mut raw_sums = vec![0.0; float_features.len() * dimensions];
+        (1..=tree_count.div_ceil(step))
+            .map(|stage| {
+                let tree_start = (stage - 1) * step;
+                let tree_end = (stage * step).min(tree_count);
+                let raw_results = self.calc_staged_prediction(
+                    float_features,
+                    cat_features,
+                    tree_start..tree_end,
+                    PredictionType::RawFormulaVal,
+                    dimensions,
+                )?;
+                for (sum, value) in raw_sums.iter_mut().zip(raw_results) {
+                    *sum += value;
+                }
+                Ok(apply_prediction_type(
+                    &raw_sums,
+                    self.prediction_type,
+                    dimensions,
+                ))
+            })
+            .collect()
     }
 
-    /// Get expected categorical feature count for model
-    pub fn get_cat_features_count(&self) -> usize {
-        unsafe { catboost_sys::GetCatFeaturesCount(self.handle) }
+    /// Compute `metrics` over the documents of a labeled pool, using the raw predictions of the
+    /// trees in `tree_start..tree_end` whatever prediction type is set on the model. Values are
+    /// returned in the order of `metrics`. The C API does not expose CatBoost's metric
+    /// evaluation, so metrics are computed by this crate and may differ from the values
+    /// reported during training in corner cases.
+    pub fn eval_metrics(
+        &self,
+        pool: &Pool,
+        metrics: &[Metric],
+        tree_start: usize,
+        tree_end: usize,
+    ) -> CatBoostResult<Vec<f64>> {
+        let labels = pool.labels().ok_or_else(|| {
+            CatBoostError::InvalidArgument(
+                "cannot evaluate metrics on a pool without labels".into(),
+            )
+        })?;
+        if pool.is_empty() {
+            return Err(CatBoostError::InvalidArgument(
+                "cannot evaluate metrics on an empty pool".into(),
+            ));
+        }
+
+        let dimensions = self.get_dimensions_count();
+        let raw_results = self.calc_staged_prediction(
+            pool.float_features(),
+            pool.cat_features(),
+            tree_start..tree_end,
+            PredictionType::RawFormulaVal,
+            dimensions,
+        )?;
+        metrics
+            .iter()
+            .map(|metric| metric.eval(&raw_results, dimensions, labels, pool.weights()))
+            .collect()
     }
 
-    /// Get number of trees in model
-    pub fn get_tree_count(&self) -> usize {
-        unsafe { catboost_sys::GetTreeCount(self.handle) }
+    /// Call the native evaluator restricted to a tree range, sizing the result buffer as
+    /// `docs * dimensions`
+    pub(crate) fn calc_staged_prediction<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+        tree_range: Range<usize>,
+        prediction_type: PredictionType,
+        dimensions: usize,
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let Range {
+            start: tree_start,
+            end: tree_end,
+        } = tree_range;
+        let tree_count = self.get_tree_count();
+        if tree_start >= tree_end || tree_end > tree_count {
+            return Err(CatBoostError::InvalidArgument(format!(
+                "invalid tree range {}..{} for a model with {} trees",
+                tree_start, tree_end, tree_count
+            )));
+        }
+        self.check_input_shape(float_features, cat_features)?;
+        if float_features.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut float_features_ptr = float_features
+            .iter()
+            .map(|x| x.as_ref().as_ptr())
+            .collect::<Vec<_>>();
+
+        let hashed_cat_features = hash_docs_cat_features(cat_features);
+        let mut hashed_cat_features_ptr = hashed_cat_features
+            .iter()
+            .map(|x| x.as_ptr())
+            .collect::<Vec<_>>();
+
+        let mut prediction = vec![0.0; float_features.len() * dimensions];
+        self.observe(prediction_type, float_features.len(), || unsafe {
+            catboost_sys::CalcModelPredictionWithHashedCatFeaturesStaged(
+                self.handle,
+                float_features.len(),
+                float_features_ptr.as_mut_ptr(),
+                float_features[0].as_ref().len(),
+                hashed_cat_features_ptr.as_mut_ptr(),
+                hashed_cat_features[0].len(),
+                tree_start,
+                tree_end,
+                prediction.as_mut_ptr(),
+                prediction.len(),
+            )
+        })?;
+        Ok(prediction)
     }
 
-    /// Get number of dimensions in model
-    pub fn get_dimensions_count(&self) -> usize {
-        unsafe { catboost_sys::GetDimensionsCount(self.handle) }
+    /// Call the native evaluator with the model's prediction type, sizing the result buffer as
+    /// `docs * dimensions`
+    pub(crate) fn calc_prediction<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+        dimensions: usize,
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        self.calc_prediction_as(
+            float_features,
+            cat_features,
+            self.prediction_type,
+            dimensions,
+        )
     }
-}
 
-impl Drop for Model {
-    fn drop(&mut self) {
-        unsafe { catboost_sys::ModelCalcerDelete(self.handle) };
+    /// Call the native evaluator with RawFormulaVal output whatever the model's prediction type,
+    /// for helpers that post-process raw values themselves
+    pub(crate) fn calc_raw_prediction<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+        dimensions: usize,
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        self.calc_prediction_as(
+            float_features,
+            cat_features,
+            PredictionType::RawFormulaVal,
+            dimensions,
+        )
     }
-}
 
-// Should be thread safe as stated here: https://github.com/catboost/catboost/issues/272
-unsafe impl Send for Model {}
+    /// Call the native evaluator with the given prediction type, sizing the result buffer as
+    /// `docs * dimensions`
+    pub(crate) fn calc_prediction_as<F, C, S>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+        prediction_type: PredictionType,
+        dimensions: usize,
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        self.check_input_shape(float_features, cat_features)?;
+        let float_features_ptr = float_features
+            .iter()
+            .map(|x| x.as_ref().as_ptr())
+            .collect::<PointerArray<_>>();
+        self.calc_prediction_from_pointers(
+            float_features_ptr,
+            self.get_float_features_count(),
+            cat_features,
+            prediction_type,
+            dimensions,
+        )
+    }
 
-unsafe impl Sync for Model {}
+    /// Check that both batches hold the same number of documents and that every document has as
+    /// many float and categorical feature values as the model expects
+    pub(crate) fn check_input_shape<F, C, T, U>(
+        &self,
+        float_features: &[F],
+        cat_features: &[C],
+    ) -> CatBoostResult<()>
+    where
+        F: AsRef<[T]>,
+        C: AsRef<[U]>,
+    {
+        check_doc_count(
+            float_features.len(),
+            cat_features.len(),
+            FeatureKind::Categorical,
+        )?;
+        check_feature_rows(
+            float_features,
+            self.get_float_features_count(),
+            FeatureKind::Float,
+        )
+        .map_err(|e| self.with_schema(e, FeatureKind::Float))?;
+        check_feature_rows(
+            cat_features,
+            self.get_cat_features_count(),
+            FeatureKind::Categorical,
+        )
+        .map_err(|e| self.with_schema(e, FeatureKind::Categorical))
+    }
 
-fn sigmoid(x: f64) -> f64 {
-    1. / (1. + (-x).exp())
-}
+    /// Check the shape of a row-major float feature matrix and the categorical feature rows
+    /// passed along with it
+    fn check_matrix_shape<C: AsRef<[U]>, U>(
+        &self,
+        rows: usize,
+        cols: usize,
+        cat_features: &[C],
+    ) -> CatBoostResult<()> {
+        let float_features_count = self.get_float_features_count();
+        if cols != float_features_count {
+            let error = CatBoostError::invalid_input(
+                format!(
+                    "expected {} float features, got {} columns",
+                    float_features_count, cols
+                ),
+                float_features_count,
+                cols,
+            );
+            return Err(self.with_schema(error, FeatureKind::Float));
+        }
+        check_doc_count(rows, cat_features.len(), FeatureKind::Categorical)?;
+        check_feature_rows(
+            cat_features,
+            self.get_cat_features_count(),
+            FeatureKind::Categorical,
+        )
+        .map_err(|e| self.with_schema(e, FeatureKind::Categorical))
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Extend a feature count mismatch error with the feature counts the model expects and,
+    /// when too few values were given, the names of the trailing features of `kind` that are
+    /// missing
+    pub(crate) fn with_schema(&self, error: CatBoostError, kind: FeatureKind) -> CatBoostError {
+        let CatBoostError::InvalidInput {
+            mut description,
+            expected,
+            got,
+        } = error
+        else {
+            return error;
+        };
 
-    #[test]
-    fn load_model() {
-        let model = Model::load("files/model.bin");
-        assert!(model.is_ok());
-    }
+        description.push_str(&format!(
+            " (model expects {} float, {} categorical and {} text features",
+            self.get_float_features_count(),
+            self.get_cat_features_count(),
+            self.get_text_features_count()
+        ));
+        if got < expected {
+            if let Ok((float_feature_names, cat_feature_names)) = self.get_feature_layout() {
+                let names = match kind {
+                    FeatureKind::Float => Some(float_feature_names),
+                    FeatureKind::Categorical => Some(cat_feature_names),
+                    FeatureKind::Text | FeatureKind::Embedding => None,
+                };
+                if let Some(missing) = names.as_ref().and_then(|names| names.get(got..expected)) {
+                    description.push_str(&format!("; missing: {}", missing.join(", ")));
+                }
+            }
+        }
+        description.push(')');
 
-    #[test]
-    fn load_model_buffer() {
-        let buffer: Vec<u8> = read_fast("files/model.bin").unwrap();
-        let model = Model::load_buffer(buffer);
-        assert!(model.is_ok());
+        CatBoostError::InvalidInput {
+            description,
+            expected,
+            got,
+        }
     }
 
-    #[test]
-    fn calc_prediction() {
-        let model = Model::load("files/model.bin").unwrap();
-        let prediction = model
-            .calc_model_prediction(
-                vec![
-                    vec![-10.0, 5.0, 753.0],
-                    vec![30.0, 1.0, 760.0],
-                    vec![40.0, 0.1, 705.0],
-                ],
-                vec![
-                    vec![String::from("north")],
-                    vec![String::from("south")],
-                    vec![String::from("south")],
-                ],
-            )
-            .unwrap();
+    /// Hash categorical features and call the native evaluator on already built float row pointers
+    fn calc_prediction_from_pointers<C, S>(
+        &self,
+        float_features_ptr: PointerArray<f32>,
+        float_features_count: usize,
+        cat_features: &[C],
+        prediction_type: PredictionType,
+        dimensions: usize,
+    ) -> CatBoostResult<Vec<f64>>
+    where
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        // Hash into one buffer rather than a vector per document
+        let cat_features_count = self.get_cat_features_count();
+        let hashed_cat_features = cat_features
+            .iter()
+            .flat_map(|doc| doc.as_ref().iter())
+            .map(|cat_feature| hash_cat_feature(cat_feature.as_ref()))
+            .collect::<SmallVec<[i32; SMALL_BATCH_SIZE * 4]>>();
+        let hashed_cat_features = (0..cat_features.len())
+            .map(|doc| {
+                &hashed_cat_features[doc * cat_features_count..(doc + 1) * cat_features_count]
+            })
+            .collect::<SmallVec<[_; SMALL_BATCH_SIZE]>>();
+        self.calc_hashed_prediction(
+            float_features_ptr,
+            float_features_count,
+            &hashed_cat_features,
+            prediction_type,
+            dimensions,
+        )
+    }
 
-        assert_eq!(prediction[0], 0.9980003729960197);
-        assert_eq!(prediction[1], 0.00249414628534181);
-        assert_eq!(prediction[2], -0.0013677527881450977);
+    /// Call the native evaluator on float row pointers and hashed categorical features
+    pub(crate) fn calc_hashed_prediction<H: AsRef<[i32]>>(
+        &self,
+        float_features_ptr: PointerArray<f32>,
+        float_features_count: usize,
+        hashed_cat_features: &[H],
+        prediction_type: PredictionType,
+        dimensions: usize,
+    ) -> CatBoostResult<Vec<f64>> {
+        let mut prediction = vec![0.0; float_features_ptr.len() * dimensions];
+        self.calc_hashed_prediction_into(
+            float_features_ptr,
+            float_features_count,
+            hashed_cat_features,
+            prediction_type,
+            &mut prediction,
+        )?;
+        Ok(prediction)
     }
 
-    #[test]
-    fn get_model_stats() {
-        let model = Model::load("files/model.bin").unwrap();
+    /// Call the native evaluator, writing results into a caller-provided buffer
+    fn calc_hashed_prediction_into<H: AsRef<[i32]>>(
+        &self,
+        mut float_features_ptr: PointerArray<f32>,
+        float_features_count: usize,
+        hashed_cat_features: &[H],
+        prediction_type: PredictionType,
+        prediction: &mut [f64],
+    ) -> CatBoostResult<()> {
+        // Empty batches are answered without calling into CatBoost
+        if float_features_ptr.is_empty() {
+            return Ok(());
+        }
+        let mut hashed_cat_features_ptr = hashed_cat_features
+            .iter()
+            .map(|x| x.as_ref().as_ptr())
+            .collect::<PointerArray<_>>();
 
-        assert_eq!(model.get_cat_features_count(), 1);
-        assert_eq!(model.get_float_features_count(), 3);
-        assert_eq!(model.get_tree_count(), 1000);
-        assert_eq!(model.get_dimensions_count(), 1);
+        self.calc_pointer_prediction_into(
+            &mut float_features_ptr,
+            float_features_count,
+            &mut hashed_cat_features_ptr,
+            hashed_cat_features[0].as_ref().len(),
+            prediction_type,
+            prediction,
+        )
     }
 
-    use std::io::Read;
-    fn read_fast<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Vec<u8>> {
-        let mut file = std::fs::File::open(path)?;
-        let meta = file.metadata()?;
-        let size = meta.len() as usize;
-        let mut data = Vec::with_capacity(size);
-        data.resize(size, 0);
-        file.read_exact(&mut data)?;
-        Ok(data)
+    /// Call the native evaluator on row pointers into float features and hashed categorical
+    /// features, which must stay valid for `float_features_count` and `cat_features_count`
+    /// values. Batches must not be empty.
+    pub(crate) fn calc_pointer_prediction_into(
+        &self,
+        float_features_ptr: &mut [*const f32],
+        float_features_count: usize,
+        hashed_cat_features_ptr: &mut [*const i32],
+        cat_features_count: usize,
+        prediction_type: PredictionType,
+        prediction: &mut [f64],
+    ) -> CatBoostResult<()> {
+        self.observe(prediction_type, float_features_ptr.len(), || unsafe {
+            catboost_sys::CalcModelPredictionWithHashedCatFeatures(
+                self.handle,
+                float_features_ptr.len(),
+                float_features_ptr.as_mut_ptr(),
+                float_features_count,
+                hashed_cat_features_ptr.as_mut_ptr(),
+                cat_features_count,
+                prediction.as_mut_ptr(),
+                prediction.len(),
+            )
+        })
+    }
+
+    /// Calculate raw model predictions on float features, string categorical feature values
+    /// and embedding features. `embedding_features` holds, for each document, one vector per
+    /// embedding feature of the model.
+    pub fn calc_model_prediction_with_embeddings(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+        embedding_features: Vec<Vec<Vec<f32>>>,
+    ) -> CatBoostResult<Vec<f64>> {
+        let text_features = vec![Vec::new(); float_features.len()];
+        self.calc_prediction_with_text_and_embeddings(
+            &float_features,
+            &cat_features,
+            &text_features,
+            &embedding_features,
+        )
     }
-}
\ No newline at end of file
+
+    /// Calculate raw model predictions through the evaluator entry point taking text and
+    /// embedding features next to float and categorical ones
+    pub(crate) fn calc_prediction_with_text_and_embeddings(
+        &self,
+        float_features: &[Vec<f32>],
+        cat_features: &[Vec<String>],
+        text_features: &[Vec<String>],
+        embedding_features: &[Vec<Vec<f32>>],
+    ) -> CatBoostResult<Vec<f64>> {
+        self.check_input_shape(float_features, cat_features)?;
+        check_doc_count(float_features.len(), text_features.len(), FeatureKind::Text)?;
+        check_feature_rows(
+            text_features,
+            self.get_text_features_count(),
+            FeatureKind::Text,
+        )?;
+        check_doc_count(
+            float_features.len(),
+            embedding_features.len(),
+            FeatureKind::Embedding,
+        )?;
+        check_feature_rows(
+            embedding_features,
+            self.get_embedding_features_count(),
+            FeatureKind::Embedding,
+        )?;
+        if float_features.is_empty() {
+            return Ok(Vec::new());
+        }
+        check_embedding_dimensions(embedding_features)?;
+        let mut float_features_ptr = float_features
+            .iter()
+            .map(|x| x.as_ptr())
+            .collect::<Vec<_>>();
+
+        let hashed_cat_features = hash_docs_cat_features(cat_features);
+        let mut hashed_cat_features_ptr = hashed_cat_features
+            .iter()
+            .map(|x| x.as_ptr())
+            .collect::<Vec<_>>();
+
+        let text_features = text_features
+            .iter()
+            .map(|doc_texts| {
+                doc_texts
+                    .iter()
+                    .map(|text| CString::new(text.as_str()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| {
+                CatBoostError::InvalidArgument("text feature value contains a NUL byte".into())
+            })?;
+        let mut doc_texts_ptr = text_features
+            .iter()
+            .map(|doc_texts| {
+                doc_texts
+                    .iter()
+                    .map(|text| text.as_ptr())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut text_features_ptr = doc_texts_ptr
+            .iter_mut()
+            .map(|x| x.as_mut_ptr())
+            .collect::<Vec<_>>();
+
+        let mut embedding_dimensions = embedding_features[0]
+            .iter()
+            .map(|embedding| embedding.len())
+            .collect::<Vec<_>>();
+        let mut doc_embeddings_ptr = embedding_features
+            .iter()
+            .map(|doc_embeddings| {
+                doc_embeddings
+                    .iter()
+                    .map(|embedding| embedding.as_ptr())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut embedding_features_ptr = doc_embeddings_ptr
+            .iter_mut()
+            .map(|x| x.as_mut_ptr())
+            .collect::<Vec<_>>();
+
+        let mut prediction =
+            vec![0.0; float_features.len() * self.prediction_dimensions(self.prediction_type)];
+        self.observe(self.prediction_type, float_features.len(), || unsafe {
+            catboost_sys::CalcModelPredictionWithHashedCatFeaturesAndTextAndEmbeddingFeatures(
+                self.handle,
+                float_features.len(),
+                float_features_ptr.as_mut_ptr(),
+                float_features[0].len(),
+                hashed_cat_features_ptr.as_mut_ptr(),
+                cat_features[0].len(),
+                text_features_ptr.as_mut_ptr(),
+                text_features[0].len(),
+                embedding_features_ptr.as_mut_ptr(),
+                embedding_dimensions.as_mut_ptr(),
+                embedding_dimensions.len(),
+                prediction.as_mut_ptr(),
+                prediction.len(),
+            )
+        })?;
+        Ok(prediction)
+    }
+
+    /// Predict the class of each document, taking the argmax over dimensions for multiclass
+    /// models and thresholding the raw value at zero (probability 0.5) for binary models.
+    /// Class labels are filled in when the model metadata stores class names. Raw values are
+    /// used whatever prediction type is set on the model.
+    pub fn predict_class(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+    ) -> CatBoostResult<Vec<ClassPrediction>> {
+        let dimensions = self.get_dimensions_count();
+        let raw_results = self.calc_raw_prediction(&float_features, &cat_features, dimensions)?;
+        let class_names = self.get_class_labels();
+
+        let predictions = raw_results
+            .chunks(dimensions)
+            .map(|doc_prediction| {
+                let index = if dimensions == 1 {
+                    usize::from(doc_prediction[0] > 0.0)
+                } else {
+                    argmax(doc_prediction)
+                };
+                ClassPrediction {
+                    index,
+                    label: class_names
+                        .as_ref()
+                        .and_then(|names| names.get(index).cloned()),
+                }
+            })
+            .collect();
+        Ok(predictions)
+    }
+
+    /// Get the `k` most probable classes of each document, most probable first. Probabilities
+    /// are computed from raw values with softmax for multiclass models and sigmoid for binary
+    /// models, whatever prediction type is set on the model.
+    pub fn predict_top_k(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+        k: usize,
+    ) -> CatBoostResult<Vec<Vec<ClassProbability>>> {
+        let dimensions = self.get_dimensions_count();
+        let raw_results = self.calc_raw_prediction(&float_features, &cat_features, dimensions)?;
+        let class_names = self.get_class_labels();
+
+        let predictions = raw_results
+            .chunks(dimensions)
+            .map(|doc_prediction| {
+                let probabilities = if dimensions == 1 {
+                    let probability = sigmoid(doc_prediction[0]);
+                    vec![1. - probability, probability]
+                } else {
+                    softmax(doc_prediction)
+                };
+                let mut classes = probabilities
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, probability)| ClassProbability {
+                        index,
+                        label: class_names
+                            .as_ref()
+                            .and_then(|names| names.get(index).cloned()),
+                        probability,
+                    })
+                    .collect::<Vec<_>>();
+                classes.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+                classes.truncate(k);
+                classes
+            })
+            .collect();
+        Ok(predictions)
+    }
+
+    /// Predict the class of each document of a binary classification model, assigning class 1
+    /// when the predicted probability is above `threshold`
+    pub fn predict_binary_with_threshold(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+        threshold: f64,
+    ) -> CatBoostResult<Vec<ClassPrediction>> {
+        let dimensions = self.get_dimensions_count();
+        if dimensions != 1 {
+            return Err(CatBoostError::InvalidArgument(format!(
+                "thresholding needs a binary model with one dimension, got {}",
+                dimensions
+            )));
+        }
+        let probabilities = self.calc_predict_proba(float_features, cat_features)?;
+        let class_names = self.get_class_labels();
+
+        let predictions = probabilities
+            .into_iter()
+            .map(|probability| {
+                let index = usize::from(probability > threshold);
+                ClassPrediction {
+                    index,
+                    label: class_names
+                        .as_ref()
+                        .and_then(|names| names.get(index).cloned()),
+                }
+            })
+            .collect();
+        Ok(predictions)
+    }
+
+    /// Get the probability threshold for binary classification stored in the model metadata, if any
+    pub fn get_probability_threshold(&self) -> Option<f64> {
+        self.get_metadata("binclass_probability_threshold")?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// Evaluate the model on the given CUDA device. Fails with a descriptive error when the
+    /// linked CatBoost library was built without CUDA support.
+    pub fn enable_gpu_evaluation(&mut self, device_id: i32) -> CatBoostResult<()> {
+        CatBoostError::check_return_value(unsafe {
+            catboost_sys::EnableGPUEvaluation(self.handle, device_id)
+        })
+        .map_err(|e| {
+            CatBoostError::NativeError(format!(
+                "failed to enable GPU evaluation on device {} (is CatBoost built with CUDA support?): {}",
+                device_id, e
+            ))
+        })?;
+        self.evaluator_type = EvaluatorType::Gpu;
+        Ok(())
+    }
+
+    /// Select the formula evaluator backend. `EvaluatorType::Gpu` uses CUDA device 0, see
+    /// `enable_gpu_evaluation` to pick another device. The C API cannot switch a model back to
+    /// CPU evaluation, so selecting `EvaluatorType::Cpu` on a GPU-evaluated model is an error.
+    pub fn set_evaluator_type(&mut self, evaluator_type: EvaluatorType) -> CatBoostResult<()> {
+        match (evaluator_type, self.evaluator_type) {
+            (EvaluatorType::Gpu, _) => self.enable_gpu_evaluation(0),
+            (EvaluatorType::Cpu, EvaluatorType::Cpu) => Ok(()),
+            (EvaluatorType::Cpu, EvaluatorType::Gpu) => Err(CatBoostError::InvalidArgument(
+                "switching from GPU back to CPU evaluation is not supported, reload the model instead"
+                    .into(),
+            )),
+        }
+    }
+
+    /// Get the formula evaluator backend currently used by the model
+    pub fn get_evaluator_type(&self) -> EvaluatorType {
+        self.evaluator_type
+    }
+
+    /// Set an observer notified after every call into the native evaluator, or remove it with
+    /// `None`. Copies made with `try_clone` share the observer.
+    pub fn set_observer(&mut self, observer: Option<Arc<dyn Observer>>) {
+        self.observer = observer;
+    }
+
+    /// Run a native prediction call on `documents` documents with the handle set to
+    /// `prediction_type`, reporting it to the observer and, with the `tracing` feature, in a span
+    fn observe<F: FnOnce() -> bool>(
+        &self,
+        prediction_type: PredictionType,
+        documents: usize,
+        call: F,
+    ) -> CatBoostResult<()> {
+        let handle = self.lock_handle(prediction_type)?;
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "catboost.predict",
+            documents,
+            duration_us = tracing::field::Empty
+        )
+        .entered();
+        let start = Instant::now();
+        let success = call();
+        let duration = start.elapsed();
+        drop(handle);
+        #[cfg(feature = "tracing")]
+        span.record("duration_us", duration.as_micros() as u64);
+
+        if let Some(observer) = &self.observer {
+            observer.on_prediction(&PredictionEvent {
+                documents,
+                duration,
+                success,
+            });
+        }
+        CatBoostError::check_return_value(success)
+    }
+
+    /// Lock the native handle for a call evaluating with `prediction_type`. Calls with the
+    /// model's own prediction type share the lock and run concurrently, calls with another one
+    /// switch the handle under an exclusive lock and switch it back once the guard is dropped.
+    fn lock_handle(&self, prediction_type: PredictionType) -> CatBoostResult<HandleGuard<'_>> {
+        if prediction_type == self.prediction_type {
+            let lock = self
+                .handle_lock
+                .read()
+                .unwrap_or_else(PoisonError::into_inner);
+            return Ok(HandleGuard::Shared { _lock: lock });
+        }
+        let lock = self
+            .handle_lock
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.set_native_prediction_type(prediction_type)?;
+        Ok(HandleGuard::Switched {
+            model: self,
+            _lock: lock,
+        })
+    }
+
+    /// Get the formula evaluator backends supported by the linked CatBoost library
+    pub fn supported_evaluator_types(&self) -> CatBoostResult<Vec<EvaluatorType>> {
+        let mut evaluator_types_ptr = std::ptr::null_mut();
+        let mut count = 0;
+        CatBoostError::check_return_value(unsafe {
+            catboost_sys::GetSupportedEvaluatorTypes(
+                self.handle,
+                &mut evaluator_types_ptr,
+                &mut count,
+            )
+        })?;
+        let evaluator_types = unsafe { take_native_array(evaluator_types_ptr, count) };
+        Ok(evaluator_types
+            .into_iter()
+            .filter_map(EvaluatorType::from_native)
+            .collect())
+    }
+
+    /// Set the type of values returned by subsequent prediction calls
+    pub fn set_prediction_type(&mut self, prediction_type: PredictionType) -> CatBoostResult<()> {
+        self.set_native_prediction_type(prediction_type)?;
+        self.prediction_type = prediction_type;
+        Ok(())
+    }
+
+    /// Set the prediction type of the native handle, leaving the model's own one unchanged
+    fn set_native_prediction_type(&self, prediction_type: PredictionType) -> CatBoostResult<()> {
+        let prediction_type_c_str = CString::new(prediction_type.as_str())
+            .map_err(|e| CatBoostError::InvalidArgument(e.to_string()))?;
+        CatBoostError::check_return_value(unsafe {
+            catboost_sys::SetPredictionTypeString(self.handle, prediction_type_c_str.as_ptr())
+        })
+    }
+
+    /// Get the type of values currently returned by prediction calls
+    pub fn get_prediction_type(&self) -> PredictionType {
+        self.prediction_type
+    }
+
+    /// Calculate `(mean, variance)` pairs for each document of a model trained with
+    /// RMSEWithUncertainty, whatever prediction type is set on the model. An error is returned
+    /// for models trained with another loss function, or without two dimensions when the loss
+    /// function is not stored.
+    pub fn calc_rmse_with_uncertainty_prediction(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+    ) -> CatBoostResult<Vec<(f64, f64)>> {
+        let trained_with_uncertainty = match self.loss_function() {
+            Some(loss_function) => loss_function == "RMSEWithUncertainty",
+            None => self.get_dimensions_count() == 2,
+        };
+        if !trained_with_uncertainty {
+            return Err(CatBoostError::InvalidArgument(format!(
+                "model was not trained with RMSEWithUncertainty (loss function {}, {} dimensions)",
+                self.loss_function().as_deref().unwrap_or("unknown"),
+                self.get_dimensions_count()
+            )));
+        }
+
+        let raw_results = self.calc_prediction_as(
+            &float_features,
+            &cat_features,
+            PredictionType::RMSEWithUncertainty,
+            self.prediction_dimensions(PredictionType::RMSEWithUncertainty),
+        )?;
+        Ok(raw_results
+            .chunks(2)
+            .map(|doc_prediction| (doc_prediction[0], doc_prediction[1]))
+            .collect())
+    }
+
+    /// Calculate probabilities with the native Probability prediction type, so values match the
+    /// ones computed by the Python package. Binary models give the probability of class 1 for
+    /// each document, multiclass models one probability per document and class. The model's
+    /// prediction type is left as it is; unless it is Probability, the call switches the native
+    /// handle under an exclusive lock and does not run concurrently with other calls on the
+    /// same model.
+    // https://catboost.ai/en/docs/concepts/output-data_model-value-output#classification
+    pub fn calc_predict_proba(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+    ) -> CatBoostResult<Vec<f64>> {
+        self.calc_prediction_as(
+            &float_features,
+            &cat_features,
+            PredictionType::Probability,
+            self.prediction_dimensions(PredictionType::Probability),
+        )
+    }
+
+    /// Get names of the features used by the model, in flat feature index order
+    pub fn get_used_feature_names(&self) -> CatBoostResult<Vec<String>> {
+        let mut names_ptr = std::ptr::null_mut();
+        let mut count = 0;
+        CatBoostError::check_return_value(unsafe {
+            catboost_sys::GetModelUsedFeaturesNames(self.handle, &mut names_ptr, &mut count)
+        })?;
+
+        let names = unsafe {
+            let names = (0..count)
+                .map(|i| {
+                    let name_ptr = *names_ptr.add(i);
+                    let name = CStr::from_ptr(name_ptr).to_string_lossy().into_owned();
+                    libc::free(name_ptr as *mut libc::c_void);
+                    name
+                })
+                .collect();
+            libc::free(names_ptr as *mut libc::c_void);
+            names
+        };
+        Ok(names)
+    }
+
+    /// Get flat indices of the float features used by the model, so serving code can skip
+    /// features the model ignores
+    pub fn get_float_feature_indices(&self) -> CatBoostResult<Vec<usize>> {
+        let mut indices_ptr = std::ptr::null_mut();
+        let mut count = 0;
+        CatBoostError::check_return_value(unsafe {
+            catboost_sys::GetFloatFeatureIndices(self.handle, &mut indices_ptr, &mut count)
+        })?;
+        Ok(unsafe { take_native_array(indices_ptr, count) })
+    }
+
+    /// Get flat indices of the categorical features used by the model
+    pub fn get_cat_feature_indices(&self) -> CatBoostResult<Vec<usize>> {
+        let mut indices_ptr = std::ptr::null_mut();
+        let mut count = 0;
+        CatBoostError::check_return_value(unsafe {
+            catboost_sys::GetCatFeatureIndices(self.handle, &mut indices_ptr, &mut count)
+        })?;
+        Ok(unsafe { take_native_array(indices_ptr, count) })
+    }
+
+    /// Get the feature names corresponding to each float and categorical feature position
+    pub(crate) fn get_feature_layout(&self) -> CatBoostResult<(Vec<String>, Vec<String>)> {
+        let names = self.get_used_feature_names()?;
+        let mut float_indices = self.get_float_feature_indices()?;
+        let mut cat_indices = self.get_cat_feature_indices()?;
+        float_indices.sort_unstable();
+        cat_indices.sort_unstable();
+
+        let mut flat_indices = float_indices
+            .iter()
+            .chain(cat_indices.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        flat_indices.sort_unstable();
+        if flat_indices.len() != names.len() {
+            return Err(CatBoostError::NativeError(format!(
+                "model stores {} feature names for {} float and categorical features",
+                names.len(),
+                flat_indices.len()
+            )));
+        }
+
+        let name_of = |flat_index: &usize| {
+            let position = flat_indices.binary_search(flat_index).unwrap();
+            names[position].clone()
+        };
+        Ok((
+            float_indices.iter().map(name_of).collect(),
+            cat_indices.iter().map(name_of).collect(),
+        ))
+    }
+
+    /// Get expected float feature count for model
+    pub fn get_float_features_count(&self) -> usize {
+        unsafe { catboost_sys::GetFloatFeaturesCount(self.handle) }
+    }
+
+    /// Get expected categorical feature count for model
+    pub fn get_cat_features_count(&self) -> usize {
+        unsafe { catboost_sys::GetCatFeaturesCount(self.handle) }
+    }
+
+    /// Get expected text feature count for model
+    pub fn get_text_features_count(&self) -> usize {
+        unsafe { catboost_sys::GetTextFeaturesCount(self.handle) }
+    }
+
+    /// Get expected embedding feature count for model
+    pub fn get_embedding_features_count(&self) -> usize {
+        unsafe { catboost_sys::GetEmbeddingFeaturesCount(self.handle) }
+    }
+
+    /// Get a value stored in the model metadata, such as training parameters (`params`), the
+    /// CatBoost version (`catboost_version_info`) or custom keys added at training time
+    pub fn get_metadata(&self, key: &str) -> Option<String> {
+        if !self.has_metadata_key(key) {
+            return None;
+        }
+        let key_ptr = key.as_ptr() as *const std::os::raw::c_char;
+        let value = unsafe {
+            let value_size = catboost_sys::GetModelInfoValueSize(self.handle, key_ptr, key.len());
+            let value_ptr = catboost_sys::GetModelInfoValue(self.handle, key_ptr, key.len());
+            std::slice::from_raw_parts(value_ptr as *const u8, value_size)
+        };
+        Some(String::from_utf8_lossy(value).into_owned())
+    }
+
+    /// Check whether the model metadata holds `key`, without fetching its value
+    pub fn has_metadata_key(&self, key: &str) -> bool {
+        unsafe {
+            catboost_sys::CheckModelMetadataHasKey(
+                self.handle,
+                key.as_ptr() as *const std::os::raw::c_char,
+                key.len(),
+            )
+        }
+    }
+
+    /// Get the well-known metadata keys present in the model. The C API cannot enumerate
+    /// metadata, so custom keys are only reachable through `get_metadata`.
+    pub fn metadata_keys(&self) -> Vec<&'static str> {
+        KNOWN_METADATA_KEYS
+            .iter()
+            .filter(|key| self.has_metadata_key(key))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the class labels of a classification model stored in the model metadata, in class
+    /// index order. Integer labels are returned in their decimal string form.
+    pub fn get_class_labels(&self) -> Option<Vec<String>> {
+        let class_params = self
+            .get_metadata("class_params")
+            .or_else(|| self.get_metadata("multiclass_params"))?;
+        parse_class_labels(&class_params)
+    }
+
+    /// Get the training parameters stored in the model metadata, such as the loss function,
+    /// iteration count and learning rate
+    pub fn training_params_json(&self) -> Option<serde_json::Value> {
+        serde_json::from_str(&self.get_metadata("params")?).ok()
+    }
+
+    /// Get the loss function the model was trained with, e.g. `Logloss`, `MultiClass` or `RMSE`,
+    /// read from the training parameters stored in the model metadata
+    pub fn loss_function(&self) -> Option<String> {
+        let params = self.training_params_json()?;
+        let loss_function = params.get("loss_function")?;
+        loss_function
+            .as_str()
+            .or_else(|| loss_function.get("type")?.as_str())
+            .map(String::from)
+    }
+
+    /// Check whether the model is a binary or multiclass classifier, based on its loss function
+    /// or, when training parameters are not stored, on the presence of class labels
+    pub fn is_classification(&self) -> bool {
+        match self.loss_function() {
+            Some(loss_function) => CLASSIFICATION_LOSS_FUNCTIONS.contains(&loss_function.as_str()),
+            None => self.get_class_labels().is_some(),
+        }
+    }
+
+    /// Check whether the model is a classifier predicting more than two classes
+    pub fn is_multiclass(&self) -> bool {
+        self.is_classification() && self.get_dimensions_count() > 1
+    }
+
+    /// Check whether the model is a regressor, i.e. neither a classifier nor a ranker
+    pub fn is_regression(&self) -> bool {
+        match self.loss_function() {
+            Some(loss_function) => {
+                !CLASSIFICATION_LOSS_FUNCTIONS.contains(&loss_function.as_str())
+                    && !RANKING_LOSS_FUNCTIONS.contains(&loss_function.as_str())
+            }
+            None => self.get_class_labels().is_none(),
+        }
+    }
+
+    /// Get the way missing float feature values were handled during training (`Min`, `Max` or
+    /// `Forbidden`), read from the training parameters stored in the model metadata
+    pub fn get_nan_mode(&self) -> Option<String> {
+        self.training_params_json()?
+            .get("data_processing_options")?
+            .get("float_features_binarization")?
+            .get("nan_mode")?
+            .as_str()
+            .map(String::from)
+    }
+
+    /// Get number of trees in model
+    pub fn get_tree_count(&self) -> usize {
+        unsafe { catboost_sys::GetTreeCount(self.handle) }
+    }
+
+    /// Get number of dimensions in model
+    pub fn get_dimensions_count(&self) -> usize {
+        unsafe { catboost_sys::GetDimensionsCount(self.handle) }
+    }
+
+    /// Number of values per document returned for `prediction_type`, as reported by the native
+    /// evaluator with the handle set to that type
+    pub(crate) fn prediction_dimensions(&self, prediction_type: PredictionType) -> usize {
+        match self.lock_handle(prediction_type) {
+            Ok(_guard) => unsafe { catboost_sys::GetPredictionDimensionsCount(self.handle) },
+            // The prediction call fails the same way, this only sizes its result buffer
+            Err(_) => self.get_dimensions_count(),
+        }
+    }
+
+    /// Get a one-line report of the model shape, loss function and key metadata, suitable for
+    /// logging which model was loaded
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "CatBoost model: {} trees, {} dimensions, {} float features, {} categorical features, {} text features, {} embedding features",
+            self.get_tree_count(),
+            self.get_dimensions_count(),
+            self.get_float_features_count(),
+            self.get_cat_features_count(),
+            self.get_text_features_count(),
+            self.get_embedding_features_count(),
+        );
+        let details = [
+            ("loss function", self.loss_function()),
+            ("model guid", self.get_metadata("model_guid")),
+            ("trained at", self.get_metadata("train_finish_time")),
+        ];
+        for (name, value) in details {
+            if let Some(value) = value {
+                summary.push_str(&format!(", {}: {}", name, value));
+            }
+        }
+        summary
+    }
+}
+
+impl Drop for Model {
+    fn drop(&mut self) {
+        unsafe { catboost_sys::ModelCalcerDelete(self.handle) };
+    }
+}
+
+// Should be thread safe as stated here: https://github.com/catboost/catboost/issues/272
+unsafe impl Send for Model {}
+
+unsafe impl Sync for Model {}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.summary())
+    }
+}
+
+impl fmt::Debug for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Model")
+            .field("tree_count", &self.get_tree_count())
+            .field("dimensions_count", &self.get_dimensions_count())
+            .field("float_features_count", &self.get_float_features_count())
+            .field("cat_features_count", &self.get_cat_features_count())
+            .field("text_features_count", &self.get_text_features_count())
+            .field(
+                "embedding_features_count",
+                &self.get_embedding_features_count(),
+            )
+            .field("prediction_type", &self.prediction_type)
+            .field("evaluator_type", &self.evaluator_type)
+            .finish()
+    }
+}
+
+/// Access to the native handle for one prediction call, see `Model::lock_handle`
+enum HandleGuard<'a> {
+    Shared {
+        _lock: RwLockReadGuard<'a, ()>,
+    },
+    Switched {
+        model: &'a Model,
+        _lock: RwLockWriteGuard<'a, ()>,
+    },
+}
+
+impl Drop for HandleGuard<'_> {
+    fn drop(&mut self) {
+        if let HandleGuard::Switched { model, .. } = self {
+            // The handle accepted this prediction type before, so switching back does not fail
+            let _ = model.set_native_prediction_type(model.prediction_type);
+        }
+    }
+}
+
+/// Predicted class of a single document
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassPrediction {
+    /// Index of the predicted class
+    pub index: usize,
+    /// Label of the predicted class, when class names are stored in the model
+    pub label: Option<String>,
+}
+
+/// Copy an array allocated by the native library and free it
+unsafe fn take_native_array<T: Copy>(ptr: *mut T, count: usize) -> Vec<T> {
+    if ptr.is_null() {
+        return Vec::new();
+    }
+    let values = std::slice::from_raw_parts(ptr, count).to_vec();
+    libc::free(ptr as *mut libc::c_void);
+    values
+}
+
+/// Read the class names of `class_params` model metadata, `None` when there are none
+fn parse_class_labels(class_params: &str) -> Option<Vec<String>> {
+    let class_params: serde_json::Value = serde_json::from_str(class_params).ok()?;
+    let class_names = class_params.get("class_names")?.as_array()?;
+    if class_names.is_empty() {
+        return None;
+    }
+    Some(
+        class_names
+            .iter()
+            .map(|name| match name {
+                serde_json::Value::String(name) => name.clone(),
+                name => name.to_string(),
+            })
+            .collect(),
+    )
+}
+
+/// Turn a failed native load into a `ModelLoad` error, describing the format of the model when
+/// its leading bytes are known. `size` is the length of the whole model, of which `header` may
+/// only hold the start.
+fn load_error(error: CatBoostError, header: Option<&[u8]>, size: usize) -> CatBoostError {
+    match header.and_then(|header| describe_model_format(header, size)) {
+        Some(format) => CatBoostError::ModelLoad(format!("{} ({})", error, format)),
+        None => CatBoostError::ModelLoad(error.to_string()),
+    }
+}
+
+/// Convert a path to the NUL-terminated string the C API takes. Unix paths are passed as their
+/// raw bytes, other platforms need paths that are valid Unicode, which CatBoost reads as UTF-8.
+fn path_to_c_string(path: &Path) -> CatBoostResult<CString> {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes()
+    };
+    #[cfg(not(unix))]
+    let bytes = path
+        .to_str()
+        .ok_or_else(|| {
+            CatBoostError::InvalidArgument(format!(
+                "path {} is not valid Unicode and cannot be passed to CatBoost",
+                path.display()
+            ))
+        })?
+        .as_bytes();
+    CString::new(bytes).map_err(|_| CatBoostError::NulInPath(path.to_path_buf()))
+}
+
+/// Read the leading bytes of a model file, or `None` when it cannot be read
+fn read_model_header(path: &Path) -> Option<Vec<u8>> {
+    let mut header = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(MODEL_HEADER_SIZE)
+        .read_to_end(&mut header)
+        .ok()?;
+    Some(header)
+}
+
+/// Describe what a model of `size` bytes starting with `buffer` looks like when CatBoost failed
+/// to load it, so load errors can tell a model exported in another format apart from a corrupt
+/// file
+fn describe_model_format(buffer: &[u8], size: usize) -> Option<&'static str> {
+    let header = &buffer[..buffer.len().min(MODEL_HEADER_SIZE as usize)];
+    let contains = |needle: &[u8]| header.windows(needle.len()).any(|window| window == needle);
+    let first_byte = buffer.iter().find(|byte| !byte.is_ascii_whitespace());
+    match first_byte {
+        None => Some("the model is empty"),
+        Some(_) if buffer.starts_with(b"CBM1") => match cbm_core_size(buffer) {
+            Some(core_size) if core_size > 0 && 8 + core_size <= size => Some(
+                "the model may have been saved by a newer CatBoost version than the linked one",
+            ),
+            _ => Some("the model file is corrupt or truncated"),
+        },
+        Some(b'{') => Some("the model looks like a JSON export, only cbm models can be loaded"),
+        Some(0x08) if contains(b"onnx") => {
+            Some("the model looks like an ONNX file, only cbm models can be loaded")
+        }
+        Some(0x08) if matches!(coreml_model_field(header), Some(302 | 402)) => {
+            Some("the model looks like a CoreML file, only cbm models can be loaded")
+        }
+        Some(_) => None,
+    }
+}
+
+/// Size of the model core following the `CBM1` signature and its own 4-byte length
+fn cbm_core_size(buffer: &[u8]) -> Option<usize> {
+    let core_size = buffer.get(4..8)?.try_into().ok()?;
+    usize::try_from(u32::from_le_bytes(core_size)).ok()
+}
+
+/// Number of the field that follows the specification version and description of a CoreML
+/// model, 302 and 402 being the tree ensemble regressor and classifier CatBoost exports
+fn coreml_model_field(header: &[u8]) -> Option<u64> {
+    let mut position = 0;
+    if read_varint(header, &mut position)? != 0x08 {
+        return None;
+    }
+    read_varint(header, &mut position)?;
+    if read_varint(header, &mut position)? != 0x12 {
+        return None;
+    }
+    let description_length = usize::try_from(read_varint(header, &mut position)?).ok()?;
+    position = position.checked_add(description_length)?;
+    let tag = read_varint(header, &mut position)?;
+    // Only length-delimited fields hold models
+    (tag & 0x7 == 2).then_some(tag >> 3)
+}
+
+/// Read a protobuf varint at `position`, moving `position` past it
+fn read_varint(bytes: &[u8], position: &mut usize) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*position)?;
+        *position += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+pub(crate) fn check_doc_count(
+    float_docs: usize,
+    other_docs: usize,
+    kind: FeatureKind,
+) -> CatBoostResult<()> {
+    if float_docs != other_docs {
+        return Err(CatBoostError::invalid_input(
+            format!(
+                "got float features for {} documents and {} features for {} documents",
+                float_docs, kind, other_docs
+            ),
+            float_docs,
+            other_docs,
+        ));
+    }
+    Ok(())
+}
+
+/// Check that each embedding feature has the same dimension in every document, as the evaluator
+/// takes a single dimension per embedding feature
+fn check_embedding_dimensions(embedding_features: &[Vec<Vec<f32>>]) -> CatBoostResult<()> {
+    let dimensions = embedding_features[0]
+        .iter()
+        .map(Vec::len)
+        .collect::<Vec<_>>();
+    for (doc_index, doc_embeddings) in embedding_features.iter().enumerate() {
+        for (embedding_index, embedding) in doc_embeddings.iter().enumerate() {
+            if embedding.len() != dimensions[embedding_index] {
+                return Err(CatBoostError::invalid_input(
+                    format!(
+                        "document {}: expected {} values for embedding feature {}, got {}",
+                        doc_index,
+                        dimensions[embedding_index],
+                        embedding_index,
+                        embedding.len()
+                    ),
+                    dimensions[embedding_index],
+                    embedding.len(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check that every document has `expected` feature values of the given kind
+fn check_feature_rows<R: AsRef<[T]>, T>(
+    docs: &[R],
+    expected: usize,
+    kind: FeatureKind,
+) -> CatBoostResult<()> {
+    for (doc_index, doc) in docs.iter().enumerate() {
+        let count = doc.as_ref().len();
+        if count != expected {
+            return Err(CatBoostError::invalid_input(
+                format!(
+                    "document {}: expected {} {} features, got {}",
+                    doc_index, expected, kind, count
+                ),
+                expected,
+                count,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Hash string categorical feature values of each document
+fn hash_docs_cat_features<C, S>(cat_features: &[C]) -> Vec<Vec<i32>>
+where
+    C: AsRef<[S]>,
+    S: AsRef<str>,
+{
+    cat_features
+        .iter()
+        .map(|doc_cat_features| hash_cat_features(doc_cat_features.as_ref()))
+        .collect::<Vec<_>>()
+}
+
+pub(crate) fn argmax(values: &[f64]) -> usize {
+    let mut best_index = 0;
+    for (index, value) in values.iter().enumerate() {
+        if *value > values[best_index] {
+            best_index = index;
+        }
+    }
+    best_index
+}
+
+fn softmax(values: &[f64]) -> Vec<f64> {
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let exps = values.iter().map(|x| (x - max).exp()).collect::<Vec<_>>();
+    let sum = exps.iter().sum::<f64>();
+    exps.into_iter().map(|x| x / sum).collect()
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1. / (1. + (-x).exp())
+}
+
+/// Turn raw formula values holding `dimensions` values per document into `prediction_type`
+/// values, the way the native evaluator does. `Class` yields one class index per document.
+fn apply_prediction_type(
+    raw_results: &[f64],
+    prediction_type: PredictionType,
+    dimensions: usize,
+) -> Vec<f64> {
+    let documents = raw_results.chunks(dimensions.max(1));
+    match prediction_type {
+        PredictionType::RawFormulaVal => raw_results.to_vec(),
+        PredictionType::Exponent => raw_results.iter().map(|x| x.exp()).collect(),
+        PredictionType::Probability if dimensions == 1 => {
+            raw_results.iter().cloned().map(sigmoid).collect()
+        }
+        PredictionType::Probability => documents.flat_map(softmax).collect(),
+        PredictionType::Class if dimensions == 1 => raw_results
+            .iter()
+            .map(|x| if *x > 0. { 1. } else { 0. })
+            .collect(),
+        PredictionType::Class => documents.map(|doc| argmax(doc) as f64).collect(),
+        PredictionType::RMSEWithUncertainty => documents
+            .flat_map(|doc| [doc[0], (2. * doc[1]).exp()])
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hash_cat_feature;
+
+    #[test]
+    fn load_model() {
+        let model = Model::load("files/model.bin");
+        assert!(model.is_ok());
+    }
+
+    #[test]
+    fn load_model_buffer() {
+        let buffer: Vec<u8> = read_fast("files/model.bin").unwrap();
+        let model = Model::load_buffer(&buffer);
+        assert!(model.is_ok());
+        let model = Model::load_buffer(&buffer[..]);
+        assert!(model.unwrap().to_bytes().is_err());
+        let model = Model::load_buffer_retained(buffer.clone());
+        assert_eq!(model.unwrap().to_bytes().unwrap(), buffer);
+    }
+
+    #[test]
+    fn load_model_in_other_format() {
+        let error = Model::load_buffer(br#"{"features_info": {}}"#).unwrap_err();
+        assert!(error.to_string().contains("JSON export"));
+
+        let describe = |buffer: &[u8]| describe_model_format(buffer, buffer.len());
+        assert!(describe(b"").is_some());
+        assert!(describe(b"CBM1\x02\x00\x00\x00\x01\x02")
+            .unwrap()
+            .contains("newer"));
+        assert!(describe(b"CBM1\x00\x00").unwrap().contains("truncated"));
+        assert!(describe(b"CBM1\x10\x00\x00\x00\x01\x02")
+            .unwrap()
+            .contains("truncated"));
+        assert!(describe(b"\x08\x07\x12\x07pytorch:\x04onnx")
+            .unwrap()
+            .contains("ONNX"));
+        assert!(describe(b"\x08\x04\x12\x02ab\x92\x19\x00")
+            .unwrap()
+            .contains("CoreML"));
+        assert!(describe(b"\x08\x04\x12\x02ab\xf2\x12\x00")
+            .unwrap()
+            .contains("CoreML"));
+        assert!(describe(b"\x08\x04\x1a\x02").is_none());
+        assert!(describe(b"\x08\x96\x01\x12\x7f").is_none());
+        assert!(describe(b"garbage").is_none());
+
+        let buffer = read_fast("files/model.bin").unwrap();
+        let error = Model::load_buffer(&buffer[..buffer.len() / 2]).unwrap_err();
+        assert!(error.to_string().contains("corrupt or truncated"));
+    }
+
+    #[test]
+    fn load_model_with_nul_in_path() {
+        let error = Model::load("files/model\0.bin").unwrap_err();
+        assert!(matches!(error, CatBoostError::NulInPath(_)));
+    }
+
+    #[test]
+    fn path_to_c_string_keeps_path_bytes() {
+        let path = path_to_c_string(Path::new("files/model.bin")).unwrap();
+        assert_eq!(path.as_bytes(), b"files/model.bin");
+        assert!(matches!(
+            path_to_c_string(Path::new("model\0.bin")),
+            Err(CatBoostError::NulInPath(_))
+        ));
+    }
+
+    #[test]
+    fn save_model() {
+        let model = Model::load("files/model.bin").unwrap();
+        assert!(model.to_bytes().is_err());
+        assert!(model.save(std::env::temp_dir().join("unused.bin")).is_err());
+
+        let model = Model::load_buffer_retained(read_fast("files/model.bin").unwrap()).unwrap();
+        let bytes = model.to_bytes().unwrap();
+        assert_eq!(bytes, read_fast("files/model.bin").unwrap());
+
+        let path = std::env::temp_dir().join("catboost_rs_save_model.bin");
+        model.save(&path).unwrap();
+        let saved_model = Model::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved_model.get_tree_count(), model.get_tree_count());
+        assert!(Model::load_buffer(bytes).is_ok());
+    }
+
+    #[test]
+    fn try_clone_model() {
+        let mut model = Model::load_buffer_retained(read_fast("files/model.bin").unwrap()).unwrap();
+        model
+            .set_prediction_type(PredictionType::Probability)
+            .unwrap();
+        let copy = model.try_clone().unwrap();
+        drop(model);
+
+        assert_eq!(copy.get_prediction_type(), PredictionType::Probability);
+        assert_eq!(copy.get_tree_count(), 1000);
+
+        let model = Model::load("files/model.bin").unwrap();
+        assert!(matches!(
+            model.try_clone(),
+            Err(CatBoostError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn calc_prediction() {
+        let model = Model::load("files/model.bin").unwrap();
+        let prediction = model
+            .calc_model_prediction(
+                vec![
+                    vec![-10.0, 5.0, 753.0],
+                    vec![30.0, 1.0, 760.0],
+                    vec![40.0, 0.1, 705.0],
+                ],
+                vec![
+                    vec![String::from("north")],
+                    vec![String::from("south")],
+                    vec![String::from("south")],
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+    }
+
+    #[test]
+    fn calc_prediction_with_missing() {
+        let model = Model::load("files/model.bin").unwrap();
+        let prediction = model
+            .calc_model_prediction_with_missing(
+                vec![
+                    vec![Some(-10.0), Some(5.0), Some(753.0)],
+                    vec![Some(30.0), None, Some(760.0)],
+                ],
+                vec![vec![String::from("north")], vec![String::from("south")]],
+            )
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction.len(), 2);
+    }
+
+    #[test]
+    fn calc_prediction_by_name() {
+        let model = Model::load("files/model.bin").unwrap();
+        let (float_feature_names, cat_feature_names) = model.get_feature_layout().unwrap();
+        let doc = float_feature_names
+            .iter()
+            .zip([-10.0, 5.0, 753.0])
+            .map(|(name, value)| (name.clone(), FeatureValue::from(value)))
+            .chain(std::iter::once((
+                cat_feature_names[0].clone(),
+                FeatureValue::from("north"),
+            )))
+            .collect::<HashMap<_, _>>();
+
+        let prediction = model
+            .calc_model_prediction_by_name(std::slice::from_ref(&doc))
+            .unwrap();
+        assert_eq!(prediction[0], 0.9980003729960197);
+
+        let mut incomplete_doc = doc;
+        incomplete_doc.remove(&cat_feature_names[0]);
+        let prediction = model.calc_model_prediction_by_name(&[incomplete_doc]);
+        assert!(prediction
+            .unwrap_err()
+            .to_string()
+            .contains(&cat_feature_names[0]));
+    }
+
+    #[test]
+    fn calc_prediction_staged() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = vec![vec![-10.0, 5.0, 753.0], vec![30.0, 1.0, 760.0]];
+        let cat_features = vec![vec![String::from("north")], vec![String::from("south")]];
+
+        let prediction = model
+            .calc_model_prediction_staged(float_features.clone(), cat_features.clone(), 0, 1000)
+            .unwrap();
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+
+        let prediction = model
+            .calc_model_prediction_staged(float_features.clone(), cat_features.clone(), 0, 100)
+            .unwrap();
+        assert_eq!(prediction.len(), 2);
+
+        assert!(model
+            .calc_model_prediction_staged(float_features, cat_features, 0, 1001)
+            .is_err());
+    }
+
+    #[test]
+    fn staged_predictions() {
+        let mut model = Model::load("files/model.bin").unwrap();
+        let float_features = [[-10.0, 5.0, 753.0], [30.0, 1.0, 760.0]];
+        let cat_features = [["north"], ["south"]];
+
+        let stages = model
+            .staged_predictions(&float_features, &cat_features, 300)
+            .unwrap();
+        assert_eq!(stages.len(), 4);
+        assert!((stages[3][0] - 0.9980003729960197).abs() < 1e-9);
+        assert!((stages[3][1] - 0.00249414628534181).abs() < 1e-9);
+        assert_eq!(
+            stages[0],
+            model
+                .calc_staged_prediction(
+                    &float_features,
+                    &cat_features,
+                    0..300,
+                    PredictionType::RawFormulaVal,
+                    1,
+                )
+                .unwrap()
+        );
+        assert!(model
+            .staged_predictions(&float_features, &cat_features, 0)
+            .is_err());
+
+        model
+            .set_prediction_type(PredictionType::Probability)
+            .unwrap();
+        let probabilities = model
+            .staged_predictions(&float_features, &cat_features, 300)
+            .unwrap();
+        for (probability, raw) in probabilities[1].iter().zip(&stages[1]) {
+            assert!((probability - sigmoid(*raw)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn eval_metrics() {
+        let mut model = Model::load("files/model.bin").unwrap();
+        let pool = Pool::builder()
+            .float_features(vec![vec![-10.0, 5.0, 753.0], vec![40.0, 0.1, 705.0]])
+            .cat_features(vec![
+                vec![String::from("north")],
+                vec![String::from("south")],
+            ])
+            .labels(vec![1.0, 0.0])
+            .build()
+            .unwrap();
+
+        let metrics = [Metric::Accuracy, Metric::AUC, Metric::Logloss];
+        let values = model.eval_metrics(&pool, &metrics, 0, 1000).unwrap();
+        assert_eq!(values[..2], [1.0, 1.0]);
+
+        model
+            .set_prediction_type(PredictionType::Probability)
+            .unwrap();
+        assert_eq!(
+            model.eval_metrics(&pool, &metrics, 0, 1000).unwrap(),
+            values
+        );
+        assert!(model
+            .eval_metrics(
+                &Pool::new(&[[0.0f32; 3]], &[["north"]]).unwrap(),
+                &[Metric::RMSE],
+                0,
+                1000
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn calc_prediction_slices() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = [[-10.0, 5.0, 753.0], [30.0, 1.0, 760.0], [40.0, 0.1, 705.0]];
+        let cat_features = [["north"], ["south"], ["south"]];
+        let prediction = model
+            .calc_model_prediction_slices(&float_features, &cat_features)
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+    }
+
+    #[test]
+    fn calc_prediction_f64() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = vec![vec![-10.0f64, 5.0, 753.0], vec![30.0, 1.0, 760.0]];
+        let cat_features = [["north"], ["south"]];
+        let prediction = model
+            .calc_model_prediction_f64(&float_features, &cat_features)
+            .unwrap();
+
+        assert_eq!(prediction, vec![0.9980003729960197, 0.00249414628534181]);
+        assert!(model
+            .calc_model_prediction_f64(&[[-10.0f64, 5.0]], &[["north"]])
+            .is_err());
+    }
+
+    #[test]
+    fn predict_into() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = [[-10.0, 5.0, 753.0], [30.0, 1.0, 760.0], [40.0, 0.1, 705.0]];
+        let cat_features = [["north"], ["south"], ["south"]];
+
+        let mut out = [0.0; 3];
+        model
+            .predict_into(&float_features, &cat_features, &mut out)
+            .unwrap();
+        assert_eq!(out[0], 0.9980003729960197);
+        assert_eq!(out[1], 0.00249414628534181);
+        assert_eq!(out[2], -0.0013677527881450977);
+
+        let mut out = [0.0; 2];
+        assert!(model
+            .predict_into(&float_features, &cat_features, &mut out)
+            .is_err());
+    }
+
+    #[test]
+    fn used_memory_bytes() {
+        let model = Model::load("files/model.bin").unwrap();
+        let file_size = std::fs::metadata("files/model.bin").unwrap().len() as usize;
+        assert_eq!(model.used_memory_bytes(), file_size);
+
+        let buffer = read_fast("files/model.bin").unwrap();
+        let retained = Model::load_buffer_retained(buffer).unwrap();
+        assert_eq!(retained.used_memory_bytes(), 2 * file_size);
+    }
+
+    #[test]
+    fn calc_prediction_chunked() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = [[-10.0, 5.0, 753.0], [30.0, 1.0, 760.0], [40.0, 0.1, 705.0]];
+        let cat_features = [["north"], ["south"], ["south"]];
+
+        let prediction = model
+            .calc_model_prediction_chunked(&float_features, &cat_features, 2)
+            .unwrap();
+        assert_eq!(
+            prediction,
+            vec![
+                0.9980003729960197,
+                0.00249414628534181,
+                -0.0013677527881450977
+            ]
+        );
+        assert!(model
+            .calc_model_prediction_chunked(&float_features, &cat_features, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn calc_prediction_hashed() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = [[-10.0, 5.0, 753.0], [30.0, 1.0, 760.0], [40.0, 0.1, 705.0]];
+        let hashed_cat_features = vec![
+            vec![hash_cat_feature("north")],
+            vec![hash_cat_feature("south")],
+            vec![hash_cat_feature("south")],
+        ];
+        let prediction = model
+            .calc_model_prediction_hashed(&float_features, &hashed_cat_features)
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+    }
+
+    #[test]
+    fn calc_prediction_matrix() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = [-10.0, 5.0, 753.0, 30.0, 1.0, 760.0, 40.0, 0.1, 705.0];
+        let cat_features = [["north"], ["south"], ["south"]];
+        let prediction = model
+            .calc_model_prediction_matrix(&float_features, (3, 3), &cat_features)
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+
+        let prediction = model.calc_model_prediction_matrix(&float_features, (2, 3), &cat_features);
+        assert!(prediction.is_err());
+    }
+
+    #[test]
+    fn calc_prediction_checks_input_shape() {
+        let model = Model::load("files/model.bin").unwrap();
+
+        let error = model
+            .calc_model_prediction_slices(
+                &[vec![-10.0, 5.0, 753.0], vec![30.0, 1.0]],
+                &[["north"], ["south"]],
+            )
+            .unwrap_err();
+        let (float_feature_names, _) = model.get_feature_layout().unwrap();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "document 1: expected 3 float features, got 2 (model expects 3 float, 1 categorical and 0 text features; missing: {})",
+                float_feature_names[2]
+            )
+        );
+        assert!(matches!(
+            error,
+            CatBoostError::InvalidInput {
+                expected: 3,
+                got: 2,
+                ..
+            }
+        ));
+
+        let error = model
+            .calc_model_prediction_slices(&[[-10.0, 5.0, 753.0]], &[[""; 0]])
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("document 0: expected 1 categorical features, got 0 (model expects"));
+
+        assert!(model
+            .calc_model_prediction_slices(&[[-10.0, 5.0, 753.0]], &[["north"], ["south"]])
+            .is_err());
+        assert!(model
+            .calc_model_prediction_matrix(&[-10.0, 5.0], (1, 2), &[["north"]])
+            .is_err());
+    }
+
+    #[test]
+    fn calc_prediction_on_empty_batch() {
+        let model = Model::load("files/model.bin").unwrap();
+
+        let prediction = model.calc_model_prediction(vec![], vec![]).unwrap();
+        assert!(prediction.is_empty());
+        let prediction = model
+            .calc_model_prediction_matrix(&[], (0, 3), &[[""; 1]; 0])
+            .unwrap();
+        assert!(prediction.is_empty());
+        assert!(model.calc_model_prediction_flat(vec![]).unwrap().is_empty());
+
+        let error = model
+            .calc_model_prediction(vec![vec![-10.0, 5.0, 753.0]], vec![])
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            CatBoostError::InvalidInput {
+                expected: 1,
+                got: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn calc_multiclass_prediction() {
+        // The test model has a single dimension, so each document gets a one-element vector;
+        // reshaping several dimensions is covered by the `MultiTargetPrediction` tests
+        let model = Model::load("files/model.bin").unwrap();
+        assert_eq!(model.get_dimensions_count(), 1);
+        let prediction = model
+            .calc_multiclass_prediction(
+                vec![
+                    vec![-10.0, 5.0, 753.0],
+                    vec![30.0, 1.0, 760.0],
+                    vec![40.0, 0.1, 705.0],
+                ],
+                vec![
+                    vec![String::from("north")],
+                    vec![String::from("south")],
+                    vec![String::from("south")],
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(prediction.len(), 3);
+        assert_eq!(prediction[0], vec![0.9980003729960197]);
+        assert_eq!(prediction[1], vec![0.00249414628534181]);
+        assert_eq!(prediction[2], vec![-0.0013677527881450977]);
+    }
+
+    #[test]
+    fn calc_prediction_with_embeddings() {
+        let model = Model::load("files/model.bin").unwrap();
+        let prediction = model
+            .calc_model_prediction_with_embeddings(
+                vec![
+                    vec![-10.0, 5.0, 753.0],
+                    vec![30.0, 1.0, 760.0],
+                    vec![40.0, 0.1, 705.0],
+                ],
+                vec![
+                    vec![String::from("north")],
+                    vec![String::from("south")],
+                    vec![String::from("south")],
+                ],
+                vec![vec![], vec![], vec![]],
+            )
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+    }
+
+    #[test]
+    fn calc_prediction_with_embeddings_rejects_ragged_rows() {
+        let model = Model::load("files/model.bin").unwrap();
+        let error = model
+            .calc_model_prediction_with_embeddings(
+                vec![vec![-10.0, 5.0, 753.0], vec![30.0, 1.0]],
+                vec![vec![String::from("north")], vec![String::from("south")]],
+                vec![vec![], vec![]],
+            )
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("document 1: expected 3 float features, got 2"));
+
+        assert!(check_embedding_dimensions(&[vec![vec![1.0, 2.0]], vec![vec![1.0]]]).is_err());
+        assert!(check_embedding_dimensions(&[vec![vec![1.0]], vec![vec![2.0]]]).is_ok());
+    }
+
+    #[test]
+    fn set_prediction_type() {
+        let mut model = Model::load("files/model.bin").unwrap();
+        model
+            .set_prediction_type(PredictionType::Probability)
+            .unwrap();
+        assert_eq!(model.get_prediction_type(), PredictionType::Probability);
+        let prediction = model
+            .calc_model_prediction(
+                vec![vec![-10.0, 5.0, 753.0]],
+                vec![vec![String::from("north")]],
+            )
+            .unwrap();
+        assert!((prediction[0] - sigmoid(0.9980003729960197)).abs() < 1e-9);
+
+        model
+            .set_prediction_type(PredictionType::RawFormulaVal)
+            .unwrap();
+        let prediction = model
+            .calc_model_prediction(
+                vec![vec![-10.0, 5.0, 753.0]],
+                vec![vec![String::from("north")]],
+            )
+            .unwrap();
+        assert_eq!(prediction[0], 0.9980003729960197);
+    }
+
+    #[test]
+    fn predict_class() {
+        let mut model = Model::load("files/model.bin").unwrap();
+        let float_features = vec![
+            vec![-10.0, 5.0, 753.0],
+            vec![30.0, 1.0, 760.0],
+            vec![40.0, 0.1, 705.0],
+        ];
+        let cat_features = vec![
+            vec![String::from("north")],
+            vec![String::from("south")],
+            vec![String::from("south")],
+        ];
+        let prediction = model
+            .predict_class(float_features.clone(), cat_features.clone())
+            .unwrap();
+
+        let indices = prediction.iter().map(|x| x.index).collect::<Vec<_>>();
+        assert_eq!(indices, vec![1, 1, 0]);
+
+        for prediction_type in [PredictionType::Probability, PredictionType::Class] {
+            model.set_prediction_type(prediction_type).unwrap();
+            let prediction = model
+                .predict_class(float_features.clone(), cat_features.clone())
+                .unwrap();
+            let indices = prediction.iter().map(|x| x.index).collect::<Vec<_>>();
+            assert_eq!(indices, vec![1, 1, 0]);
+            assert_eq!(model.get_prediction_type(), prediction_type);
+        }
+    }
+
+    #[test]
+    fn calc_predict_proba() {
+        let model = Model::load("files/model.bin").unwrap();
+        let probabilities = model
+            .calc_predict_proba(
+                vec![vec![-10.0, 5.0, 753.0], vec![30.0, 1.0, 760.0]],
+                vec![vec![String::from("north")], vec![String::from("south")]],
+            )
+            .unwrap();
+
+        assert_eq!(probabilities.len(), 2);
+        assert!((probabilities[0] - sigmoid(0.9980003729960197)).abs() < 1e-9);
+        assert!((probabilities[1] - sigmoid(0.00249414628534181)).abs() < 1e-9);
+        assert_eq!(model.get_prediction_type(), PredictionType::RawFormulaVal);
+
+        // Probability calls on a shared model do not leak into concurrent raw calls
+        let float_features = vec![vec![-10.0, 5.0, 753.0]];
+        let cat_features = vec![vec![String::from("north")]];
+        std::thread::scope(|scope| {
+            for thread in 0..4 {
+                let model = &model;
+                let (float_features, cat_features) = (&float_features, &cat_features);
+                scope.spawn(move || {
+                    for _ in 0..50 {
+                        if thread.is_multiple_of(2) {
+                            let probabilities = model
+                                .calc_predict_proba(float_features.clone(), cat_features.clone())
+                                .unwrap();
+                            assert!((probabilities[0] - sigmoid(0.9980003729960197)).abs() < 1e-9);
+                        } else {
+                            let prediction = model
+                                .calc_model_prediction(float_features.clone(), cat_features.clone())
+                                .unwrap();
+                            assert_eq!(prediction, vec![0.9980003729960197]);
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn predict_binary_with_threshold() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = vec![
+            vec![-10.0, 5.0, 753.0],
+            vec![30.0, 1.0, 760.0],
+            vec![40.0, 0.1, 705.0],
+        ];
+        let cat_features = vec![
+            vec![String::from("north")],
+            vec![String::from("south")],
+            vec![String::from("south")],
+        ];
+
+        let prediction = model
+            .predict_binary_with_threshold(float_features.clone(), cat_features.clone(), 0.5)
+            .unwrap();
+        let indices = prediction.iter().map(|x| x.index).collect::<Vec<_>>();
+        assert_eq!(indices, vec![1, 1, 0]);
+
+        let prediction = model
+            .predict_binary_with_threshold(float_features, cat_features, 0.9)
+            .unwrap();
+        let indices = prediction.iter().map(|x| x.index).collect::<Vec<_>>();
+        assert_eq!(indices, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn predict_top_k() {
+        let mut model = Model::load("files/model.bin").unwrap();
+        let float_features = vec![vec![-10.0, 5.0, 753.0], vec![40.0, 0.1, 705.0]];
+        let cat_features = vec![vec![String::from("north")], vec![String::from("south")]];
+        let prediction = model
+            .predict_top_k(float_features.clone(), cat_features.clone(), 1)
+            .unwrap();
+
+        assert_eq!(prediction[0].len(), 1);
+        assert_eq!(prediction[0][0].index, 1);
+        assert_eq!(prediction[1][0].index, 0);
+        assert!((prediction[0][0].probability - sigmoid(0.9980003729960197)).abs() < 1e-12);
+
+        model
+            .set_prediction_type(PredictionType::Probability)
+            .unwrap();
+        assert_eq!(
+            model
+                .predict_top_k(float_features, cat_features, 1)
+                .unwrap(),
+            prediction
+        );
+    }
+
+    #[test]
+    fn softmax_sums_to_one() {
+        let probabilities = softmax(&[1.0, 2.0, 3.0]);
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(probabilities[2] > probabilities[1] && probabilities[1] > probabilities[0]);
+    }
+
+    #[test]
+    fn apply_prediction_type_to_raw_values() {
+        let raw = [0.5, -1.0, 2.0, 0.0];
+        assert_eq!(
+            apply_prediction_type(&raw, PredictionType::RawFormulaVal, 1),
+            raw.to_vec()
+        );
+        assert_eq!(
+            apply_prediction_type(&raw, PredictionType::Class, 1),
+            vec![1., 0., 1., 0.]
+        );
+        assert_eq!(
+            apply_prediction_type(&raw, PredictionType::Class, 2),
+            vec![0., 0.]
+        );
+        assert_eq!(
+            apply_prediction_type(&raw, PredictionType::Probability, 1)[3],
+            0.5
+        );
+        let probabilities = apply_prediction_type(&raw, PredictionType::Probability, 2);
+        assert!((probabilities[0] + probabilities[1] - 1.0).abs() < 1e-12);
+        assert!(probabilities[2] > probabilities[3]);
+        assert_eq!(
+            apply_prediction_type(&raw, PredictionType::Exponent, 1)[1],
+            (-1.0f64).exp()
+        );
+        assert_eq!(
+            apply_prediction_type(&raw, PredictionType::RMSEWithUncertainty, 2),
+            vec![0.5, (-2.0f64).exp(), 2.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn calc_rmse_with_uncertainty_prediction_needs_uncertainty_model() {
+        let model = Model::load("files/model.bin").unwrap();
+        let result = model.calc_rmse_with_uncertainty_prediction(
+            vec![vec![-10.0, 5.0, 753.0]],
+            vec![vec![String::from("north")]],
+        );
+        assert!(matches!(result, Err(CatBoostError::InvalidArgument(_))));
+        assert_eq!(model.get_prediction_type(), PredictionType::RawFormulaVal);
+    }
+
+    #[test]
+    fn get_class_labels() {
+        // The test model is a regressor, which stores no class names
+        let model = Model::load("files/model.bin").unwrap();
+        assert!(!model.is_classification());
+        assert_eq!(model.get_class_labels(), None);
+    }
+
+    #[test]
+    fn parse_class_labels_from_metadata() {
+        assert_eq!(
+            parse_class_labels(r#"{"class_names": ["cat", "dog", "bird"]}"#),
+            Some(vec![
+                String::from("cat"),
+                String::from("dog"),
+                String::from("bird")
+            ])
+        );
+        assert_eq!(
+            parse_class_labels(r#"{"class_label_type": "Integer", "class_names": [0, 1]}"#),
+            Some(vec![String::from("0"), String::from("1")])
+        );
+        assert_eq!(parse_class_labels(r#"{"class_names": []}"#), None);
+        assert_eq!(parse_class_labels(r#"{"class_to_label": [0, 1]}"#), None);
+        assert_eq!(parse_class_labels("not json"), None);
+    }
+
+    #[test]
+    fn argmax_picks_first_maximum() {
+        assert_eq!(argmax(&[0.1, 0.7, 0.2]), 1);
+        assert_eq!(argmax(&[0.5, 0.5]), 0);
+    }
+
+    #[test]
+    fn evaluator_types() {
+        let mut model = Model::load("files/model.bin").unwrap();
+        let supported = model.supported_evaluator_types().unwrap();
+
+        assert!(supported.contains(&EvaluatorType::Cpu));
+        assert_eq!(model.get_evaluator_type(), EvaluatorType::Cpu);
+        assert!(model.set_evaluator_type(EvaluatorType::Cpu).is_ok());
+    }
+
+    #[test]
+    fn get_used_feature_names() {
+        let model = Model::load("files/model.bin").unwrap();
+        let names = model.get_used_feature_names().unwrap();
+
+        assert_eq!(
+            names.len(),
+            model.get_float_features_count() + model.get_cat_features_count()
+        );
+    }
+
+    #[test]
+    fn get_feature_indices() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_indices = model.get_float_feature_indices().unwrap();
+        let cat_indices = model.get_cat_feature_indices().unwrap();
+
+        assert_eq!(float_indices.len(), 3);
+        assert_eq!(cat_indices.len(), 1);
+        assert!(float_indices
+            .iter()
+            .all(|index| !cat_indices.contains(index)));
+    }
+
+    #[test]
+    fn get_metadata() {
+        let model = Model::load("files/model.bin").unwrap();
+
+        assert!(model.get_metadata("params").is_some());
+        assert!(model.get_metadata("no_such_key").is_none());
+        assert!(model.metadata_keys().contains(&"params"));
+        assert!(model.has_metadata_key("params"));
+        assert!(!model.has_metadata_key("no_such_key"));
+    }
+
+    #[test]
+    fn training_params_json() {
+        let model = Model::load("files/model.bin").unwrap();
+        let params = model.training_params_json().unwrap();
+
+        assert!(params.get("loss_function").is_some());
+        assert!(model.loss_function().is_some());
+    }
+
+    #[test]
+    fn model_kind() {
+        let model = Model::load("files/model.bin").unwrap();
+
+        assert_ne!(model.is_classification(), model.is_regression());
+        assert!(!model.is_multiclass());
+    }
+
+    #[test]
+    fn get_model_stats() {
+        let model = Model::load("files/model.bin").unwrap();
+
+        assert_eq!(model.get_cat_features_count(), 1);
+        assert_eq!(model.get_float_features_count(), 3);
+        assert_eq!(model.get_text_features_count(), 0);
+        assert_eq!(model.get_embedding_features_count(), 0);
+        assert_eq!(model.get_tree_count(), 1000);
+        assert_eq!(model.get_dimensions_count(), 1);
+    }
+
+    #[test]
+    fn model_summary() {
+        let model = Model::load("files/model.bin").unwrap();
+        let summary = model.summary();
+
+        assert!(summary.contains("1000 trees"));
+        assert!(summary.contains("3 float features"));
+        assert!(summary.contains("1 categorical features"));
+        assert_eq!(model.to_string(), summary);
+        assert!(format!("{:?}", model).contains("tree_count: 1000"));
+    }
+
+    fn read_fast<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Vec<u8>> {
+        let mut file = std::fs::File::open(path)?;
+        let meta = file.metadata()?;
+        let size = meta.len() as usize;
+        let mut data = Vec::with_capacity(size);
+        data.resize(size, 0);
+        file.read_exact(&mut data)?;
+        Ok(data)
+    }
+}
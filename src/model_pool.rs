@@ -0,0 +1,136 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+use std::cell::Cell;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Source of the indices threads use to pick their model from a pool
+static NEXT_THREAD_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_INDEX: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Index of the current thread, handed out in the order threads first use a pool
+fn thread_index() -> usize {
+    THREAD_INDEX.with(|index| match index.get() {
+        Some(index) => index,
+        None => {
+            let next = NEXT_THREAD_INDEX.fetch_add(1, Ordering::Relaxed);
+            index.set(Some(next));
+            next
+        }
+    })
+}
+
+/// Separate handles of one model, each loaded from its serialized form, so that threads
+/// scoring concurrently do not contend inside a shared native evaluator. Every thread is routed
+/// to the same handle on each call, and threads only share a handle once there are more of
+/// them than handles in the pool.
+#[derive(Debug)]
+pub struct ModelPool {
+    models: Vec<Model>,
+}
+
+impl ModelPool {
+    /// Create a pool of `size` handles of `model`, keeping its prediction type. Only works for
+    /// models loaded with `Model::load_buffer_retained`, as models from `Model::load` and
+    /// `Model::load_buffer` do not keep the serialized model; use `ModelPool::load` to build a
+    /// pool straight from a model file.
+    pub fn new(model: &Model, size: usize) -> CatBoostResult<Self> {
+        check_size(size)?;
+        let models = (0..size)
+            .map(|_| model.try_clone())
+            .collect::<CatBoostResult<Vec<_>>>()?;
+        Ok(ModelPool { models })
+    }
+
+    /// Create a pool of `size` handles of the model in a CatBoost binary (cbm) file, which is
+    /// read once and loaded into every handle
+    pub fn load<P: AsRef<Path>>(path: P, size: usize) -> CatBoostResult<Self> {
+        check_size(size)?;
+        let path = path.as_ref();
+        CatBoostError::check_path(path)?;
+        let buffer = std::fs::read(path).map_err(|e| {
+            CatBoostError::Io(format!(
+                "failed to read model file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let models = (0..size)
+            .map(|_| Model::load_buffer(&buffer))
+            .collect::<CatBoostResult<Vec<_>>>()?;
+        Ok(ModelPool { models })
+    }
+
+    /// Create a pool with one handle of `model` per available CPU
+    pub fn with_available_parallelism(model: &Model) -> CatBoostResult<Self> {
+        let size = std::thread::available_parallelism().map_or(1, |size| size.get());
+        ModelPool::new(model, size)
+    }
+
+    /// Get the handle of the current thread
+    pub fn get(&self) -> &Model {
+        &self.models[thread_index() % self.models.len()]
+    }
+
+    /// Get the number of handles in the pool
+    pub fn size(&self) -> usize {
+        self.models.len()
+    }
+}
+
+fn check_size(size: usize) -> CatBoostResult<()> {
+    if size == 0 {
+        return Err(CatBoostError::InvalidArgument(
+            "pool size must be greater than zero".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_pool() {
+        let model = Model::load("files/model.bin").unwrap();
+        assert!(ModelPool::new(&model, 2).is_err());
+
+        let model = Model::load_buffer_retained(std::fs::read("files/model.bin").unwrap()).unwrap();
+        assert!(ModelPool::new(&model, 0).is_err());
+
+        let pool = ModelPool::new(&model, 2).unwrap();
+        assert_eq!(pool.size(), 2);
+        assert!(std::ptr::eq(pool.get(), pool.get()));
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let prediction = pool
+                        .get()
+                        .calc_model_prediction(
+                            vec![vec![-10.0, 5.0, 753.0]],
+                            vec![vec![String::from("north")]],
+                        )
+                        .unwrap();
+                    assert_eq!(prediction, vec![0.9980003729960197]);
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn load_model_pool() {
+        let pool = ModelPool::load("files/model.bin", 3).unwrap();
+        assert_eq!(pool.size(), 3);
+        assert_eq!(pool.get().get_tree_count(), 1000);
+        assert!(ModelPool::load("files/model.bin", 0).is_err());
+        assert!(matches!(
+            ModelPool::load("files/no_such_model.bin", 2),
+            Err(CatBoostError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+/// Receives an event for every call into the native evaluator of a model it is set on with
+/// `Model::set_observer`, e.g. to feed Prometheus counters and latency histograms without
+/// wrapping every call site. Events are delivered on the thread making the call, so
+/// implementations should return quickly.
+pub trait Observer: Send + Sync {
+    /// Called after the evaluator returns
+    fn on_prediction(&self, event: &PredictionEvent);
+}
+
+/// A single call into the native evaluator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PredictionEvent {
+    /// Number of documents in the batch
+    pub documents: usize,
+    /// Time spent in the evaluator
+    pub duration: Duration,
+    /// Whether the evaluator reported success
+    pub success: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Model;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder {
+        documents: Mutex<Vec<usize>>,
+    }
+
+    impl Observer for Recorder {
+        fn on_prediction(&self, event: &PredictionEvent) {
+            assert!(event.success);
+            self.documents.lock().unwrap().push(event.documents);
+        }
+    }
+
+    #[test]
+    fn observe_predictions() {
+        // Keep the serialized model so the observer can be checked on a copy
+        let buffer = std::fs::read("files/model.bin").unwrap();
+        let mut model = Model::load_buffer_retained(buffer).unwrap();
+        let recorder = Arc::new(Recorder::default());
+        model.set_observer(Some(recorder.clone()));
+
+        model
+            .calc_model_prediction(
+                vec![vec![-10.0, 5.0, 753.0], vec![30.0, 1.0, 760.0]],
+                vec![vec![String::from("north")], vec![String::from("south")]],
+            )
+            .unwrap();
+        model
+            .try_clone()
+            .unwrap()
+            .calc_model_prediction(
+                vec![vec![40.0, 0.1, 705.0]],
+                vec![vec![String::from("south")]],
+            )
+            .unwrap();
+        assert_eq!(*recorder.documents.lock().unwrap(), vec![2, 1]);
+
+        model.set_observer(None);
+        model
+            .calc_model_prediction(
+                vec![vec![40.0, 0.1, 705.0]],
+                vec![vec![String::from("south")]],
+            )
+            .unwrap();
+        assert_eq!(recorder.documents.lock().unwrap().len(), 2);
+    }
+}
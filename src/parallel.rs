@@ -0,0 +1,61 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+use rayon::prelude::*;
+
+impl Model {
+    /// Calculate raw model predictions on float features and string categorical feature values,
+    /// splitting the batch into chunks of `chunk_size` documents that are scored on the rayon
+    /// thread pool. Predictions are returned in input order.
+    pub fn calc_model_prediction_parallel(
+        &self,
+        float_features: Vec<Vec<f32>>,
+        cat_features: Vec<Vec<String>>,
+        chunk_size: usize,
+    ) -> CatBoostResult<Vec<f64>> {
+        if chunk_size == 0 {
+            return Err(CatBoostError::InvalidArgument(
+                "chunk size must be greater than zero".into(),
+            ));
+        }
+        self.check_input_shape(&float_features, &cat_features)?;
+
+        let dimensions = self.prediction_dimensions(self.get_prediction_type());
+        let predictions = float_features
+            .par_chunks(chunk_size)
+            .zip(cat_features.par_chunks(chunk_size))
+            .map(|(float_chunk, cat_chunk)| {
+                self.calc_prediction(float_chunk, cat_chunk, dimensions)
+            })
+            .collect::<CatBoostResult<Vec<_>>>()?;
+        Ok(predictions.concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calc_prediction_parallel() {
+        let model = Model::load("files/model.bin").unwrap();
+        let prediction = model
+            .calc_model_prediction_parallel(
+                vec![
+                    vec![-10.0, 5.0, 753.0],
+                    vec![30.0, 1.0, 760.0],
+                    vec![40.0, 0.1, 705.0],
+                ],
+                vec![
+                    vec![String::from("north")],
+                    vec![String::from("south")],
+                    vec![String::from("south")],
+                ],
+                2,
+            )
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+    }
+}
@@ -0,0 +1,489 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::feature::FeatureKind;
+use crate::model::Model;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Values CatBoost treats as missing in numeric columns of pool files
+const MISSING_VALUES: &[&str] = &[
+    "", "#N/A", "#NA", "-", "N/A", "NA", "NULL", "NaN", "-NaN", "nan", "-nan", "na", "null",
+    "None", "none",
+];
+
+/// Type of a pool file column, as declared in the column description file
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ColumnType {
+    Num,
+    Categ,
+    Label,
+    Weight,
+    GroupId,
+    Text,
+    Auxiliary,
+    Other,
+}
+
+impl ColumnType {
+    fn parse(column_type: &str) -> Self {
+        match column_type {
+            "Num" => ColumnType::Num,
+            "Categ" => ColumnType::Categ,
+            "Label" | "Target" => ColumnType::Label,
+            "Weight" => ColumnType::Weight,
+            "GroupId" | "QueryId" => ColumnType::GroupId,
+            "Text" => ColumnType::Text,
+            "Auxiliary" => ColumnType::Auxiliary,
+            _ => ColumnType::Other,
+        }
+    }
+}
+
+/// A dataset split into float features, categorical features, labels, weights and group ids,
+/// loaded from CatBoost's native pool format or built from Rust values
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pool {
+    float_features: Vec<Vec<f32>>,
+    cat_features: Vec<Vec<String>>,
+    labels: Option<Vec<f32>>,
+    weights: Option<Vec<f32>>,
+    group_ids: Option<Vec<String>>,
+    float_feature_names: Vec<Option<String>>,
+    cat_feature_names: Vec<Option<String>>,
+    cat_feature_indices: Vec<usize>,
+}
+
+impl Pool {
+    /// Create a pool from float and categorical feature values of each document. Float features
+    /// come first in the feature layout, followed by categorical features.
+    pub fn new<F, C, S>(float_features: &[F], cat_features: &[C]) -> CatBoostResult<Self>
+    where
+        F: AsRef<[f32]>,
+        C: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        Pool::builder()
+            .float_features(
+                float_features
+                    .iter()
+                    .map(|doc| doc.as_ref().to_vec())
+                    .collect(),
+            )
+            .cat_features(
+                cat_features
+                    .iter()
+                    .map(|doc| doc.as_ref().iter().map(|x| x.as_ref().to_owned()).collect())
+                    .collect(),
+            )
+            .build()
+    }
+
+    /// Start building a pool from feature values, labels, weights and group ids
+    pub fn builder() -> PoolBuilder {
+        PoolBuilder::default()
+    }
+
+    /// Load a pool from a tab-separated data file without a header and a column description
+    /// (cd) file. Columns not listed in the cd file are numeric features.
+    pub fn from_file<P: AsRef<Path>, C: AsRef<Path>>(
+        data_path: P,
+        cd_path: C,
+    ) -> CatBoostResult<Self> {
+        let column_description = parse_column_description(&read_file(cd_path.as_ref())?)?;
+        parse_pool(&read_file(data_path.as_ref())?, &column_description)
+    }
+
+    /// Get number of documents
+    pub fn len(&self) -> usize {
+        self.float_features.len()
+    }
+
+    /// Check whether the pool has no documents
+    pub fn is_empty(&self) -> bool {
+        self.float_features.is_empty()
+    }
+
+    /// Get float feature values of each document
+    pub fn float_features(&self) -> &[Vec<f32>] {
+        &self.float_features
+    }
+
+    /// Get categorical feature values of each document
+    pub fn cat_features(&self) -> &[Vec<String>] {
+        &self.cat_features
+    }
+
+    /// Get labels, when the pool has a label column
+    pub fn labels(&self) -> Option<&[f32]> {
+        self.labels.as_deref()
+    }
+
+    /// Get weights, when the pool has a weight column
+    pub fn weights(&self) -> Option<&[f32]> {
+        self.weights.as_deref()
+    }
+
+    /// Get group ids, when the pool has a group id column
+    pub fn group_ids(&self) -> Option<&[String]> {
+        self.group_ids.as_deref()
+    }
+
+    /// Get names of the float features, as declared in the cd file or set on the builder
+    pub fn float_feature_names(&self) -> &[Option<String>] {
+        &self.float_feature_names
+    }
+
+    /// Get names of the categorical features, as declared in the cd file or set on the builder
+    pub fn cat_feature_names(&self) -> &[Option<String>] {
+        &self.cat_feature_names
+    }
+
+    /// Get the indices of the categorical features among all features of the pool
+    pub fn cat_feature_indices(&self) -> &[usize] {
+        &self.cat_feature_indices
+    }
+}
+
+/// Builder for a pool constructed from Rust values, checking that every column has one value
+/// per document
+#[derive(Debug, Clone, Default)]
+pub struct PoolBuilder {
+    float_features: Vec<Vec<f32>>,
+    cat_features: Vec<Vec<String>>,
+    labels: Option<Vec<f32>>,
+    weights: Option<Vec<f32>>,
+    group_ids: Option<Vec<String>>,
+    float_feature_names: Option<Vec<String>>,
+    cat_feature_names: Option<Vec<String>>,
+}
+
+impl PoolBuilder {
+    /// Set float feature values of each document
+    pub fn float_features(mut self, float_features: Vec<Vec<f32>>) -> Self {
+        self.float_features = float_features;
+        self
+    }
+
+    /// Set categorical feature values of each document
+    pub fn cat_features(mut self, cat_features: Vec<Vec<String>>) -> Self {
+        self.cat_features = cat_features;
+        self
+    }
+
+    /// Set the label of each document
+    pub fn labels(mut self, labels: Vec<f32>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Set the weight of each document
+    pub fn weights(mut self, weights: Vec<f32>) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Set the group id of each document, for ranking datasets
+    pub fn group_ids(mut self, group_ids: Vec<String>) -> Self {
+        self.group_ids = Some(group_ids);
+        self
+    }
+
+    /// Set names of the float features
+    pub fn float_feature_names(mut self, names: Vec<String>) -> Self {
+        self.float_feature_names = Some(names);
+        self
+    }
+
+    /// Set names of the categorical features
+    pub fn cat_feature_names(mut self, names: Vec<String>) -> Self {
+        self.cat_feature_names = Some(names);
+        self
+    }
+
+    /// Build the pool, returning an error when columns disagree on the number of documents or
+    /// documents disagree on the number of features
+    pub fn build(self) -> CatBoostResult<Pool> {
+        let doc_count = self.float_features.len().max(self.cat_features.len());
+        let float_features =
+            fill_missing_docs(self.float_features, doc_count, "float feature rows")?;
+        let cat_features =
+            fill_missing_docs(self.cat_features, doc_count, "categorical feature rows")?;
+        let float_features_count = feature_count(&float_features, FeatureKind::Float)?;
+        let cat_features_count = feature_count(&cat_features, FeatureKind::Categorical)?;
+
+        check_len(self.labels.as_deref(), doc_count, "labels")?;
+        check_len(self.weights.as_deref(), doc_count, "weights")?;
+        check_len(self.group_ids.as_deref(), doc_count, "group ids")?;
+        check_len(
+            self.float_feature_names.as_deref(),
+            float_features_count,
+            "float feature names",
+        )?;
+        check_len(
+            self.cat_feature_names.as_deref(),
+            cat_features_count,
+            "categorical feature names",
+        )?;
+
+        let names = |names: Option<Vec<String>>, count: usize| match names {
+            Some(names) => names.into_iter().map(Some).collect(),
+            None => vec![None; count],
+        };
+        Ok(Pool {
+            float_features,
+            cat_features,
+            labels: self.labels,
+            weights: self.weights,
+            group_ids: self.group_ids,
+            float_feature_names: names(self.float_feature_names, float_features_count),
+            cat_feature_names: names(self.cat_feature_names, cat_features_count),
+            cat_feature_indices: (float_features_count..float_features_count + cat_features_count)
+                .collect(),
+        })
+    }
+}
+
+/// Give every document an empty feature list when a feature kind was not set at all
+fn fill_missing_docs<T>(
+    docs: Vec<Vec<T>>,
+    doc_count: usize,
+    column: &str,
+) -> CatBoostResult<Vec<Vec<T>>> {
+    if docs.is_empty() {
+        return Ok((0..doc_count).map(|_| Vec::new()).collect());
+    }
+    check_len(Some(&docs), doc_count, column)?;
+    Ok(docs)
+}
+
+fn feature_count<T>(docs: &[Vec<T>], kind: FeatureKind) -> CatBoostResult<usize> {
+    let count = docs.first().map_or(0, Vec::len);
+    match docs.iter().position(|doc| doc.len() != count) {
+        Some(doc_index) => Err(CatBoostError::invalid_input(
+            format!(
+                "document {}: expected {} {} features, got {}",
+                doc_index,
+                count,
+                kind,
+                docs[doc_index].len()
+            ),
+            count,
+            docs[doc_index].len(),
+        )),
+        None => Ok(count),
+    }
+}
+
+fn check_len<T>(values: Option<&[T]>, expected: usize, column: &str) -> CatBoostResult<()> {
+    match values {
+        Some(values) if values.len() != expected => Err(CatBoostError::invalid_input(
+            format!("expected {} {}, got {}", expected, column, values.len()),
+            expected,
+            values.len(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+impl Model {
+    /// Calculate raw model predictions on every document of a pool
+    pub fn predict_pool(&self, pool: &Pool) -> CatBoostResult<Vec<f64>> {
+        self.calc_prediction(
+            &pool.float_features,
+            &pool.cat_features,
+            self.prediction_dimensions(self.get_prediction_type()),
+        )
+    }
+}
+
+fn read_file(path: &Path) -> CatBoostResult<String> {
+    CatBoostError::check_path(path)?;
+    fs::read_to_string(path)
+        .map_err(|e| CatBoostError::Io(format!("failed to read {}: {}", path.display(), e)))
+}
+
+/// Parse a column description file into column types and optional names keyed by column index
+fn parse_column_description(
+    contents: &str,
+) -> CatBoostResult<HashMap<usize, (ColumnType, Option<String>)>> {
+    let mut columns = HashMap::new();
+    for (line_index, line) in contents.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split('\t');
+        let column_index = parts
+            .next()
+            .and_then(|index| index.trim().parse::<usize>().ok())
+            .ok_or_else(|| {
+                CatBoostError::Parse(format!(
+                    "cd line {}: expected a column index, got {:?}",
+                    line_index + 1,
+                    line
+                ))
+            })?;
+        let column_type = parts.next().map(str::trim).ok_or_else(|| {
+            CatBoostError::Parse(format!("cd line {}: missing column type", line_index + 1))
+        })?;
+        let name = parts.next().map(|name| name.trim().to_owned());
+        columns.insert(column_index, (ColumnType::parse(column_type), name));
+    }
+    Ok(columns)
+}
+
+fn parse_pool(
+    contents: &str,
+    column_description: &HashMap<usize, (ColumnType, Option<String>)>,
+) -> CatBoostResult<Pool> {
+    let column_type = |column: usize| {
+        column_description
+            .get(&column)
+            .map_or(ColumnType::Num, |(column_type, _)| *column_type)
+    };
+    let column_name = |column: usize| {
+        column_description
+            .get(&column)
+            .and_then(|(_, name)| name.clone())
+    };
+
+    let mut pool = Pool::default();
+    let mut column_count = None;
+    for (line_index, line) in contents.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            continue;
+        }
+        let values = line.split('\t').collect::<Vec<_>>();
+        match column_count {
+            None => {
+                column_count = Some(values.len());
+                let mut feature_index = 0;
+                for column in 0..values.len() {
+                    match column_type(column) {
+                        ColumnType::Num => pool.float_feature_names.push(column_name(column)),
+                        ColumnType::Categ => {
+                            pool.cat_feature_names.push(column_name(column));
+                            pool.cat_feature_indices.push(feature_index);
+                        }
+                        ColumnType::Label => pool.labels = Some(Vec::new()),
+                        ColumnType::Weight => pool.weights = Some(Vec::new()),
+                        ColumnType::GroupId => pool.group_ids = Some(Vec::new()),
+                        _ => {}
+                    }
+                    if matches!(
+                        column_type(column),
+                        ColumnType::Num | ColumnType::Categ | ColumnType::Text
+                    ) {
+                        feature_index += 1;
+                    }
+                }
+            }
+            Some(count) if count != values.len() => {
+                return Err(CatBoostError::Parse(format!(
+                    "line {}: expected {} columns, got {}",
+                    line_index + 1,
+                    count,
+                    values.len()
+                )))
+            }
+            Some(_) => {}
+        }
+
+        let mut doc_float_features = Vec::with_capacity(pool.float_feature_names.len());
+        let mut doc_cat_features = Vec::with_capacity(pool.cat_feature_names.len());
+        for (column, value) in values.into_iter().enumerate() {
+            match column_type(column) {
+                ColumnType::Num => doc_float_features.push(parse_value(value, line_index)?),
+                ColumnType::Categ => doc_cat_features.push(value.to_owned()),
+                ColumnType::Label => {
+                    let label = parse_value(value, line_index)?;
+                    pool.labels.get_or_insert_with(Vec::new).push(label);
+                }
+                ColumnType::Weight => {
+                    let weight = parse_value(value, line_index)?;
+                    pool.weights.get_or_insert_with(Vec::new).push(weight);
+                }
+                ColumnType::GroupId => {
+                    let group_id = value.to_owned();
+                    pool.group_ids.get_or_insert_with(Vec::new).push(group_id);
+                }
+                _ => {}
+            }
+        }
+        pool.float_features.push(doc_float_features);
+        pool.cat_features.push(doc_cat_features);
+    }
+    Ok(pool)
+}
+
+fn parse_value(value: &str, line_index: usize) -> CatBoostResult<f32> {
+    if MISSING_VALUES.contains(&value) {
+        return Ok(f32::NAN);
+    }
+    value.parse().map_err(|_| {
+        CatBoostError::Parse(format!(
+            "line {}: cannot parse {:?} as a number",
+            line_index + 1,
+            value
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pool_with_column_description() {
+        let column_description =
+            parse_column_description("0\tLabel\n2\tCateg\tdirection\n4\tAuxiliary\n").unwrap();
+        let pool = parse_pool(
+            "1\t-10.0\tnorth\t5.0\tid1\n0\t30.0\tsouth\tNaN\tid2\n",
+            &column_description,
+        )
+        .unwrap();
+
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.labels(), Some(&[1.0, 0.0][..]));
+        assert_eq!(pool.weights(), None);
+        assert_eq!(pool.float_features()[0], vec![-10.0, 5.0]);
+        assert!(pool.float_features()[1][1].is_nan());
+        assert_eq!(pool.cat_features()[1], vec![String::from("south")]);
+        assert_eq!(pool.cat_feature_names(), &[Some(String::from("direction"))]);
+        assert_eq!(pool.cat_feature_indices(), &[1]);
+    }
+
+    #[test]
+    fn build_pool() {
+        let pool = Pool::builder()
+            .float_features(vec![vec![-10.0, 5.0], vec![30.0, 1.0]])
+            .cat_features(vec![
+                vec![String::from("north")],
+                vec![String::from("south")],
+            ])
+            .labels(vec![1.0, 0.0])
+            .group_ids(vec![String::from("a"), String::from("a")])
+            .build()
+            .unwrap();
+
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.cat_feature_indices(), &[2]);
+        assert_eq!(pool.float_feature_names(), &[None, None]);
+        assert_eq!(pool.labels(), Some(&[1.0, 0.0][..]));
+        assert_eq!(pool.group_ids().map(<[_]>::len), Some(2));
+
+        assert!(Pool::builder()
+            .float_features(vec![vec![1.0], vec![2.0]])
+            .labels(vec![1.0])
+            .build()
+            .is_err());
+        assert!(Pool::new(&[vec![1.0], vec![2.0, 3.0]], &[[""; 0]; 2]).is_err());
+    }
+
+    #[test]
+    fn parse_pool_rejects_ragged_lines() {
+        let column_description = parse_column_description("0\tLabel\n").unwrap();
+        assert!(parse_pool("1\t2.0\n0\n", &column_description).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+/// Predictions of a model with several outputs per document, such as MultiRMSE or multiclass
+/// models, stored as one flat buffer of `docs * dimensions` values
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiTargetPrediction {
+    values: Vec<f64>,
+    dimensions: usize,
+}
+
+impl MultiTargetPrediction {
+    /// Create predictions from a flat buffer of `docs * dimensions` values, e.g. to return from
+    /// a mock `Predictor`
+    pub fn new(values: Vec<f64>, dimensions: usize) -> Self {
+        MultiTargetPrediction { values, dimensions }
+    }
+
+    /// Get number of outputs per document
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Get number of documents
+    pub fn len(&self) -> usize {
+        self.values.len().checked_div(self.dimensions).unwrap_or(0)
+    }
+
+    /// Check whether there are no documents
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the outputs of a single document
+    pub fn get(&self, doc: usize) -> Option<&[f64]> {
+        let start = doc.checked_mul(self.dimensions)?;
+        self.values.get(start..start + self.dimensions)
+    }
+
+    /// Iterate over the outputs of each document
+    pub fn iter(&self) -> std::slice::Chunks<'_, f64> {
+        self.values.chunks(self.dimensions.max(1))
+    }
+
+    /// Get the flat buffer of all outputs, document by document
+    pub fn as_flat(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Convert into one vector of outputs per document
+    pub fn into_vec(self) -> Vec<Vec<f64>> {
+        self.iter().map(|doc| doc.to_vec()).collect()
+    }
+}
+
+/// Probability of a single class for one document
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassProbability {
+    /// Index of the class
+    pub index: usize,
+    /// Label of the class, when class names are stored in the model
+    pub label: Option<String>,
+    /// Predicted probability of the class
+    pub probability: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reshape_by_dimensions() {
+        let prediction = MultiTargetPrediction::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 3);
+
+        assert_eq!(prediction.len(), 2);
+        assert_eq!(prediction.get(1), Some(&[4.0, 5.0, 6.0][..]));
+        assert_eq!(prediction.get(2), None);
+        assert_eq!(
+            prediction.into_vec(),
+            vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]
+        );
+    }
+}
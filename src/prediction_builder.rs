@@ -0,0 +1,138 @@
+use crate::error::CatBoostResult;
+use crate::model::Model;
+use crate::prediction::MultiTargetPrediction;
+use crate::prediction_type::PredictionType;
+use std::ops::Range;
+
+impl Model {
+    /// Start building a prediction call with optional settings such as the prediction type or
+    /// a tree range, e.g. `model.prediction().floats(rows).cats(rows).run()`
+    pub fn prediction(&self) -> PredictionBuilder<'_> {
+        PredictionBuilder {
+            model: self,
+            float_features: Vec::new(),
+            cat_features: Vec::new(),
+            prediction_type: None,
+            tree_range: None,
+        }
+    }
+}
+
+/// Builder for a prediction call, created by `Model::prediction`. Settings only apply to the call
+/// being built, the model's own prediction type is left unchanged. There is no thread count
+/// setting, as the C API evaluates each call on the calling thread; use
+/// `Model::calc_model_prediction_parallel` or a `ModelPool` to score on several threads.
+#[derive(Debug)]
+pub struct PredictionBuilder<'a> {
+    model: &'a Model,
+    float_features: Vec<Vec<f32>>,
+    cat_features: Vec<Vec<String>>,
+    prediction_type: Option<PredictionType>,
+    tree_range: Option<Range<usize>>,
+}
+
+impl<'a> PredictionBuilder<'a> {
+    /// Set float feature values of each document
+    pub fn floats(mut self, float_features: Vec<Vec<f32>>) -> Self {
+        self.float_features = float_features;
+        self
+    }
+
+    /// Set categorical feature values of each document. Can be left out for models without
+    /// categorical features.
+    pub fn cats(mut self, cat_features: Vec<Vec<String>>) -> Self {
+        self.cat_features = cat_features;
+        self
+    }
+
+    /// Set the type of values to return, instead of the prediction type set on the model. Unless
+    /// it is the model's own type, the call switches the native handle under an exclusive lock
+    /// and does not run concurrently with other calls on the same model.
+    pub fn prediction_type(mut self, prediction_type: PredictionType) -> Self {
+        self.prediction_type = Some(prediction_type);
+        self
+    }
+
+    /// Only use the trees in `tree_range`, instead of all trees of the model
+    pub fn tree_range(mut self, tree_range: Range<usize>) -> Self {
+        self.tree_range = Some(tree_range);
+        self
+    }
+
+    /// Calculate the predictions, holding as many values per document as the prediction type
+    /// gives: one per model dimension, a class index for `PredictionType::Class` or
+    /// `(mean, variance)` pairs for `PredictionType::RMSEWithUncertainty`
+    pub fn run(self) -> CatBoostResult<MultiTargetPrediction> {
+        let PredictionBuilder {
+            model,
+            float_features,
+            mut cat_features,
+            prediction_type,
+            tree_range,
+        } = self;
+        if cat_features.is_empty() && model.get_cat_features_count() == 0 {
+            cat_features = vec![Vec::new(); float_features.len()];
+        }
+
+        let prediction_type = prediction_type.unwrap_or(model.get_prediction_type());
+        let dimensions = model.prediction_dimensions(prediction_type);
+        let raw_results = match tree_range {
+            Some(tree_range) => model.calc_staged_prediction(
+                &float_features,
+                &cat_features,
+                tree_range,
+                prediction_type,
+                dimensions,
+            ),
+            None => model.calc_prediction_as(
+                &float_features,
+                &cat_features,
+                prediction_type,
+                dimensions,
+            ),
+        };
+        Ok(MultiTargetPrediction::new(raw_results?, dimensions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predict_with_builder() {
+        let model = Model::load("files/model.bin").unwrap();
+        let float_features = vec![vec![-10.0, 5.0, 753.0], vec![30.0, 1.0, 760.0]];
+        let cat_features = vec![vec![String::from("north")], vec![String::from("south")]];
+
+        let prediction = model
+            .prediction()
+            .floats(float_features.clone())
+            .cats(cat_features.clone())
+            .run()
+            .unwrap();
+        assert_eq!(
+            prediction.as_flat(),
+            &[0.9980003729960197, 0.00249414628534181]
+        );
+
+        let prediction = model
+            .prediction()
+            .floats(float_features.clone())
+            .cats(cat_features.clone())
+            .prediction_type(PredictionType::Probability)
+            .tree_range(0..1000)
+            .run()
+            .unwrap();
+        assert!(prediction.as_flat()[0] > 0.5);
+        assert_eq!(model.get_prediction_type(), PredictionType::RawFormulaVal);
+
+        assert!(model
+            .prediction()
+            .floats(float_features)
+            .cats(cat_features)
+            .tree_range(0..1001)
+            .run()
+            .is_err());
+    }
+}
@@ -0,0 +1,29 @@
+/// Type of values returned by the native evaluator. Each document gets one value per model
+/// dimension, except for `Class`, which gives a single class index, and `RMSEWithUncertainty`,
+/// which gives a mean and a variance; prediction results hold that many values per document.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PredictionType {
+    /// Raw formula values, the default
+    RawFormulaVal,
+    /// Probabilities, with sigmoid or softmax applied depending on the loss function
+    Probability,
+    /// Predicted class index of each document
+    Class,
+    /// Exponent of raw formula values, for Poisson and Tweedie style losses
+    Exponent,
+    /// Mean and variance pairs for models trained with RMSEWithUncertainty
+    RMSEWithUncertainty,
+}
+
+impl PredictionType {
+    /// Name of the prediction type as understood by `SetPredictionTypeString`
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PredictionType::RawFormulaVal => "RawFormulaVal",
+            PredictionType::Probability => "Probability",
+            PredictionType::Class => "Class",
+            PredictionType::Exponent => "Exponent",
+            PredictionType::RMSEWithUncertainty => "RMSEWithUncertainty",
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::batch::PredictionBatch;
+use crate::error::CatBoostResult;
+use crate::model::Model;
+use crate::prediction::MultiTargetPrediction;
+
+/// Anything that scores batches of documents, implemented by `Model`. Taking a
+/// `&dyn Predictor` or a generic `P: Predictor` instead of a `Model` lets applications swap in a
+/// mock in unit tests or another backend without changing call sites.
+pub trait Predictor {
+    /// Calculate predictions on a batch, holding as many values per document as the prediction
+    /// type gives
+    fn predict(&self, batch: &PredictionBatch) -> CatBoostResult<MultiTargetPrediction>;
+}
+
+impl Predictor for Model {
+    fn predict(&self, batch: &PredictionBatch) -> CatBoostResult<MultiTargetPrediction> {
+        Ok(MultiTargetPrediction::new(
+            self.predict_batch(batch)?,
+            self.prediction_dimensions(self.get_prediction_type()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::PredictionBatchBuilder;
+
+    struct SumPredictor;
+
+    impl Predictor for SumPredictor {
+        fn predict(&self, batch: &PredictionBatch) -> CatBoostResult<MultiTargetPrediction> {
+            let sums = (0..batch.len())
+                .map(|doc| batch.float_features(doc).unwrap().iter().sum::<f32>() as f64)
+                .collect();
+            Ok(MultiTargetPrediction::new(sums, 1))
+        }
+    }
+
+    fn first_prediction(predictor: &dyn Predictor, batch: &PredictionBatch) -> f64 {
+        predictor.predict(batch).unwrap().as_flat()[0]
+    }
+
+    #[test]
+    fn predictor() {
+        let model = Model::load("files/model.bin").unwrap();
+        let mut builder = model.batch_builder();
+        builder.push(&[-10.0, 5.0, 753.0], &["north"]).unwrap();
+        let batch = builder.build();
+        assert_eq!(first_prediction(&model, &batch), 0.9980003729960197);
+        assert_eq!(
+            model.predict(&batch).unwrap().as_flat(),
+            &[0.9980003729960197]
+        );
+
+        let mut builder = PredictionBatchBuilder::new(2, 0);
+        builder.push::<&str>(&[1.0, 2.0], &[]).unwrap();
+        assert!(builder.push::<&str>(&[1.0], &[]).is_err());
+        let batch = builder.build();
+        assert_eq!(first_prediction(&SumPredictor, &batch), 3.0);
+    }
+}
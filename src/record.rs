@@ -0,0 +1,121 @@
+use crate::error::CatBoostResult;
+use crate::model::Model;
+
+/// A Rust value that can be turned into the float and categorical features of one document.
+///
+/// With the `derive` feature this can be derived for structs, marking fields with
+/// `#[catboost(float)]` or `#[catboost(cat)]`.
+pub trait ScoreRecord {
+    /// Float feature values, in model order
+    fn float_features(&self) -> Vec<f32>;
+
+    /// Categorical feature values, in model order
+    fn cat_features(&self) -> Vec<String>;
+}
+
+/// Float and categorical feature values of a document, already in model order
+impl ScoreRecord for (Vec<f32>, Vec<String>) {
+    fn float_features(&self) -> Vec<f32> {
+        self.0.clone()
+    }
+
+    fn cat_features(&self) -> Vec<String> {
+        self.1.clone()
+    }
+}
+
+impl Model {
+    /// Calculate raw model predictions on records implementing `ScoreRecord`
+    pub fn predict_records<R: ScoreRecord>(&self, records: &[R]) -> CatBoostResult<Vec<f64>> {
+        let float_features = records
+            .iter()
+            .map(ScoreRecord::float_features)
+            .collect::<Vec<_>>();
+        let cat_features = records
+            .iter()
+            .map(ScoreRecord::cat_features)
+            .collect::<Vec<_>>();
+        self.calc_prediction(
+            &float_features,
+            &cat_features,
+            self.prediction_dimensions(self.get_prediction_type()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Row {
+        temperature: f32,
+        pressure: f32,
+        altitude: f32,
+        direction: &'static str,
+    }
+
+    impl ScoreRecord for Row {
+        fn float_features(&self) -> Vec<f32> {
+            vec![self.temperature, self.pressure, self.altitude]
+        }
+
+        fn cat_features(&self) -> Vec<String> {
+            vec![self.direction.to_string()]
+        }
+    }
+
+    #[test]
+    fn predict_records() {
+        let model = Model::load("files/model.bin").unwrap();
+        let prediction = model
+            .predict_records(&[
+                Row {
+                    temperature: -10.0,
+                    pressure: 5.0,
+                    altitude: 753.0,
+                    direction: "north",
+                },
+                Row {
+                    temperature: 30.0,
+                    pressure: 1.0,
+                    altitude: 760.0,
+                    direction: "south",
+                },
+            ])
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn predict_derived_records() {
+        #[derive(crate::ScoreRecord)]
+        struct DerivedRow {
+            #[catboost(float)]
+            temperature: f64,
+            #[catboost(float)]
+            pressure: f32,
+            #[catboost(float)]
+            altitude: i32,
+            #[catboost(cat)]
+            direction: String,
+            #[allow(dead_code)]
+            id: u64,
+        }
+
+        let model = Model::load("files/model.bin").unwrap();
+        let prediction = model
+            .predict_records(&[DerivedRow {
+                temperature: -10.0,
+                pressure: 5.0,
+                altitude: 753,
+                direction: String::from("north"),
+                id: 1,
+            }])
+            .unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::hash::hash_cat_feature;
+use crate::model::{Model, PointerArray};
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::datatypes::{DataType, Float32Type, Float64Type};
+use arrow::record_batch::RecordBatch;
+
+impl Model {
+    /// Calculate raw model predictions on an Arrow record batch.
+    ///
+    /// Float32 and Float64 columns are used as float features and string or dictionary-encoded
+    /// string columns as categorical features, both in column order. Null float values are passed
+    /// to the model as NaN, which CatBoost treats as missing.
+    pub fn predict_record_batch(&self, batch: &RecordBatch) -> CatBoostResult<Vec<f64>> {
+        let rows = batch.num_rows();
+        let schema = batch.schema();
+
+        let mut float_columns = Vec::new();
+        let mut cat_columns = Vec::new();
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            match column.data_type() {
+                DataType::Float32 | DataType::Float64 => float_columns.push(column),
+                DataType::Utf8 | DataType::LargeUtf8 | DataType::Dictionary(_, _) => {
+                    cat_columns.push((field.name(), column))
+                }
+                data_type => {
+                    return Err(CatBoostError::InvalidArgument(format!(
+                        "column {} has unsupported type {}",
+                        field.name(),
+                        data_type
+                    )))
+                }
+            }
+        }
+
+        let cols = float_columns.len();
+        let float_features_count = self.get_float_features_count();
+        if cols != float_features_count {
+            return Err(CatBoostError::invalid_input(
+                format!(
+                    "expected {} float columns, got {}",
+                    float_features_count, cols
+                ),
+                float_features_count,
+                cols,
+            ));
+        }
+        let cat_features_count = self.get_cat_features_count();
+        if cat_columns.len() != cat_features_count {
+            return Err(CatBoostError::invalid_input(
+                format!(
+                    "expected {} categorical columns, got {}",
+                    cat_features_count,
+                    cat_columns.len()
+                ),
+                cat_features_count,
+                cat_columns.len(),
+            ));
+        }
+        let mut float_features = vec![0.0; rows * cols];
+        for (col, column) in float_columns.into_iter().enumerate() {
+            if let Some(column) = column.as_primitive_opt::<Float32Type>() {
+                for (row, value) in column.values().iter().enumerate() {
+                    float_features[row * cols + col] = if column.is_null(row) {
+                        f32::NAN
+                    } else {
+                        *value
+                    };
+                }
+            } else if let Some(column) = column.as_primitive_opt::<Float64Type>() {
+                for (row, value) in column.values().iter().enumerate() {
+                    float_features[row * cols + col] = if column.is_null(row) {
+                        f32::NAN
+                    } else {
+                        *value as f32
+                    };
+                }
+            }
+        }
+
+        let mut hashed_cat_features = vec![vec![0; cat_columns.len()]; rows];
+        for (col, (name, column)) in cat_columns.into_iter().enumerate() {
+            let hashes = hash_string_column(name, column)?;
+            for (row, hash) in hashes.into_iter().enumerate() {
+                hashed_cat_features[row][col] = hash;
+            }
+        }
+
+        let float_features_ptr = (0..rows)
+            .map(|row| float_features[row * cols..].as_ptr())
+            .collect::<PointerArray<_>>();
+        self.calc_hashed_prediction(
+            float_features_ptr,
+            cols,
+            &hashed_cat_features,
+            self.get_prediction_type(),
+            self.prediction_dimensions(self.get_prediction_type()),
+        )
+    }
+}
+
+/// Hash every value of a string column, hashing each distinct value of a dictionary-encoded
+/// column only once
+fn hash_string_column(name: &str, column: &ArrayRef) -> CatBoostResult<Vec<i32>> {
+    if column.null_count() > 0 {
+        return Err(CatBoostError::InvalidArgument(format!(
+            "categorical column {} contains null values",
+            name
+        )));
+    }
+
+    if let Some(dictionary) = column.as_any_dictionary_opt() {
+        let value_hashes = hash_string_column(name, dictionary.values())?;
+        return Ok(dictionary
+            .normalized_keys()
+            .into_iter()
+            .map(|key| value_hashes[key])
+            .collect());
+    }
+
+    if let Some(column) = column.as_string_opt::<i32>() {
+        Ok(column
+            .iter()
+            .map(|value| hash_cat_feature(value.unwrap_or_default()))
+            .collect())
+    } else if let Some(column) = column.as_string_opt::<i64>() {
+        Ok(column
+            .iter()
+            .map(|value| hash_cat_feature(value.unwrap_or_default()))
+            .collect())
+    } else {
+        Err(CatBoostError::InvalidArgument(format!(
+            "categorical column {} has unsupported type {}",
+            name,
+            column.data_type()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{DictionaryArray, Float32Array, StringArray};
+    use arrow::datatypes::Int32Type;
+    use std::sync::Arc;
+
+    fn float_column(values: Vec<f32>) -> ArrayRef {
+        Arc::new(Float32Array::from(values))
+    }
+
+    #[test]
+    fn predict_record_batch() {
+        let model = Model::load("files/model.bin").unwrap();
+        let batch = RecordBatch::try_from_iter(vec![
+            ("a", float_column(vec![-10.0, 30.0, 40.0])),
+            ("b", float_column(vec![5.0, 1.0, 0.1])),
+            ("c", float_column(vec![753.0, 760.0, 705.0])),
+            (
+                "d",
+                Arc::new(StringArray::from(vec!["north", "south", "south"])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let prediction = model.predict_record_batch(&batch).unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+    }
+
+    #[test]
+    fn predict_record_batch_dictionary() {
+        let model = Model::load("files/model.bin").unwrap();
+        let dictionary = vec!["north", "south", "south"]
+            .into_iter()
+            .collect::<DictionaryArray<Int32Type>>();
+        let batch = RecordBatch::try_from_iter(vec![
+            ("a", float_column(vec![-10.0, 30.0, 40.0])),
+            ("b", float_column(vec![5.0, 1.0, 0.1])),
+            ("c", float_column(vec![753.0, 760.0, 705.0])),
+            ("d", Arc::new(dictionary) as ArrayRef),
+        ])
+        .unwrap();
+        let prediction = model.predict_record_batch(&batch).unwrap();
+
+        assert_eq!(prediction[0], 0.9980003729960197);
+        assert_eq!(prediction[1], 0.00249414628534181);
+        assert_eq!(prediction[2], -0.0013677527881450977);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::error::CatBoostResult;
+use crate::model::Model;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+/// A cache of models loaded on demand, shared behind `Arc<Model>`. Models are looked up by a
+/// name registered with `register`, or by their path otherwise. Concurrent requests for a model
+/// that is not loaded yet wait for a single load. When a memory limit is set, least recently
+/// used models are evicted once the memory used by the cached models, as estimated by
+/// `Model::used_memory_bytes`, exceeds it; callers still holding an evicted model can keep
+/// using it.
+#[derive(Default)]
+pub struct ModelRegistry {
+    max_memory_bytes: Option<usize>,
+    state: Mutex<RegistryState>,
+    loaded: Condvar,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    paths: HashMap<String, PathBuf>,
+    models: HashMap<String, CachedModel>,
+    loading: HashSet<String>,
+    clock: u64,
+}
+
+struct CachedModel {
+    model: Arc<Model>,
+    size: usize,
+    last_used: u64,
+}
+
+impl ModelRegistry {
+    /// Create a registry without a memory limit
+    pub fn new() -> Self {
+        ModelRegistry::default()
+    }
+
+    /// Create a registry evicting least recently used models once the cached models take more
+    /// than `max_memory_bytes`
+    pub fn with_max_memory(max_memory_bytes: usize) -> Self {
+        ModelRegistry {
+            max_memory_bytes: Some(max_memory_bytes),
+            ..ModelRegistry::default()
+        }
+    }
+
+    /// Register the file a named model is loaded from. A model already cached under that name
+    /// is evicted, so the next `get` loads the new file.
+    pub fn register<N: Into<String>, P: Into<PathBuf>>(&self, name: N, path: P) {
+        let name = name.into();
+        let mut state = self.lock();
+        state.models.remove(&name);
+        state.paths.insert(name, path.into());
+    }
+
+    /// Get a model by registered name or path, loading it when it is not cached
+    pub fn get(&self, name: &str) -> CatBoostResult<Arc<Model>> {
+        let mut state = self.lock();
+        loop {
+            state.clock += 1;
+            let clock = state.clock;
+            if let Some(cached) = state.models.get_mut(name) {
+                cached.last_used = clock;
+                return Ok(Arc::clone(&cached.model));
+            }
+            if !state.loading.contains(name) {
+                break;
+            }
+            state = self
+                .loaded
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+
+        let path = state
+            .paths
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(name));
+        state.loading.insert(name.to_owned());
+        drop(state);
+
+        let model = Model::load(&path).map(Arc::new);
+
+        let mut state = self.lock();
+        state.loading.remove(name);
+        if let Ok(model) = &model {
+            state.clock += 1;
+            let cached = CachedModel {
+                model: Arc::clone(model),
+                size: model.used_memory_bytes(),
+                last_used: state.clock,
+            };
+            state.models.insert(name.to_owned(), cached);
+            if let Some(max_memory_bytes) = self.max_memory_bytes {
+                evict_to_fit(&mut state, name, max_memory_bytes);
+            }
+        }
+        drop(state);
+        self.loaded.notify_all();
+        model
+    }
+
+    /// Remove a model from the cache, returning whether it was cached
+    pub fn evict(&self, name: &str) -> bool {
+        self.lock().models.remove(name).is_some()
+    }
+
+    /// Remove all models from the cache. Registered names are kept.
+    pub fn clear(&self) {
+        self.lock().models.clear();
+    }
+
+    /// Get the number of cached models
+    pub fn len(&self) -> usize {
+        self.lock().models.len()
+    }
+
+    /// Check whether no models are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the memory used by the cached models in bytes, as estimated by
+    /// `Model::used_memory_bytes`
+    pub fn memory_bytes(&self) -> usize {
+        self.lock().models.values().map(|cached| cached.size).sum()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RegistryState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Evict least recently used models, other than the one just loaded, until the cache fits
+fn evict_to_fit(state: &mut RegistryState, loaded_name: &str, max_memory_bytes: usize) {
+    loop {
+        let memory_bytes: usize = state.models.values().map(|cached| cached.size).sum();
+        if memory_bytes <= max_memory_bytes {
+            return;
+        }
+        let least_recently_used = state
+            .models
+            .iter()
+            .filter(|(name, _)| name.as_str() != loaded_name)
+            .min_by_key(|(_, cached)| cached.last_used)
+            .map(|(name, _)| name.clone());
+        match least_recently_used {
+            Some(name) => state.models.remove(&name),
+            None => return,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_cached_model() {
+        let registry = ModelRegistry::new();
+        registry.register("model", "files/model.bin");
+
+        let model = registry.get("model").unwrap();
+        assert!(Arc::ptr_eq(&model, &registry.get("model").unwrap()));
+        assert!(Arc::ptr_eq(
+            &registry.get("files/model.bin").unwrap(),
+            &registry.get("files/model.bin").unwrap()
+        ));
+        assert_eq!(registry.len(), 2);
+
+        assert!(registry.evict("model"));
+        assert!(!Arc::ptr_eq(&model, &registry.get("model").unwrap()));
+        assert!(registry.get("files/no_such_model.bin").is_err());
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let registry = ModelRegistry::with_max_memory(1);
+        registry.register("first", "files/model.bin");
+        registry.register("second", "files/model.bin");
+
+        let first = registry.get("first").unwrap();
+        registry.get("second").unwrap();
+
+        assert_eq!(registry.len(), 1);
+        assert!(!Arc::ptr_eq(&first, &registry.get("first").unwrap()));
+        assert_eq!(first.get_tree_count(), 1000);
+    }
+
+    #[test]
+    fn evict_by_estimated_memory() {
+        let model_bytes = std::fs::metadata("files/model.bin").unwrap().len() as usize;
+        let registry = ModelRegistry::with_max_memory(2 * model_bytes);
+        for name in ["first", "second", "third"] {
+            registry.register(name, "files/model.bin");
+        }
+
+        registry.get("first").unwrap();
+        registry.get("second").unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.memory_bytes(), 2 * model_bytes);
+
+        registry.get("first").unwrap();
+        registry.get("third").unwrap();
+        assert_eq!(registry.len(), 2);
+        assert!(registry.evict("first"));
+        assert!(!registry.evict("second"));
+    }
+
+    #[test]
+    fn deduplicate_concurrent_loads() {
+        let registry = Arc::new(ModelRegistry::new());
+        let models = (0..4)
+            .map(|_| {
+                let registry = Arc::clone(&registry);
+                std::thread::spawn(move || registry.get("files/model.bin").unwrap())
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(models.iter().all(|model| Arc::ptr_eq(model, &models[0])));
+    }
+}
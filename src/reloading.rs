@@ -0,0 +1,108 @@
+use crate::error::{CatBoostError, CatBoostResult};
+use crate::model::Model;
+use notify::Watcher;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// A model that is reloaded whenever its file changes on disk. Callers take a snapshot of the
+/// current model with `model`, so predictions in flight keep using the previous model until
+/// they finish and its handle is freed.
+pub struct ReloadingModel {
+    path: PathBuf,
+    current: Arc<RwLock<Arc<Model>>>,
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl ReloadingModel {
+    /// Load the model at `path` and start watching the file for changes
+    pub fn new<P: AsRef<Path>>(path: P) -> CatBoostResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let current = Arc::new(RwLock::new(Arc::new(Model::load(&path)?)));
+
+        let watched_path = path.clone();
+        let watched_model = Arc::clone(&current);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if let Ok(event) = event {
+                    let changes_model = (event.kind.is_create() || event.kind.is_modify())
+                        && event
+                            .paths
+                            .iter()
+                            .any(|path| path.file_name() == watched_path.file_name());
+                    if changes_model {
+                        // A file that fails to load, e.g. because it is still being written,
+                        // leaves the current model in place until the next change
+                        let _ = reload(&watched_path, &watched_model);
+                    }
+                }
+            })
+            .map_err(watch_error)?;
+
+        // Watch the directory rather than the file, so a model replaced by renaming a new file
+        // over the old one is picked up too
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        watcher
+            .watch(directory, notify::RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        Ok(ReloadingModel {
+            path,
+            current,
+            _watcher: watcher,
+        })
+    }
+
+    /// Get the currently loaded model
+    pub fn model(&self) -> Arc<Model> {
+        Arc::clone(&self.current.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Reload the model from disk immediately. The current model is kept when loading fails.
+    pub fn reload(&self) -> CatBoostResult<()> {
+        reload(&self.path, &self.current)
+    }
+
+    /// Get the path of the watched model file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn reload(path: &Path, current: &RwLock<Arc<Model>>) -> CatBoostResult<()> {
+    let model = Arc::new(Model::load(path)?);
+    *current.write().unwrap_or_else(PoisonError::into_inner) = model;
+    Ok(())
+}
+
+fn watch_error(error: notify::Error) -> CatBoostError {
+    CatBoostError::Io(format!("failed to watch model file: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_model() {
+        let directory = std::env::temp_dir().join("catboost_rs_reload_model");
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("model.bin");
+        std::fs::copy("files/model.bin", &path).unwrap();
+
+        let reloading_model = ReloadingModel::new(&path).unwrap();
+        let previous_model = reloading_model.model();
+        reloading_model.reload().unwrap();
+
+        assert!(!Arc::ptr_eq(&previous_model, &reloading_model.model()));
+        assert_eq!(previous_model.get_tree_count(), 1000);
+
+        std::fs::write(&path, b"not a model").unwrap();
+        assert!(reloading_model.reload().is_err());
+        assert_eq!(reloading_model.model().get_tree_count(), 1000);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
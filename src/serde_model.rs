@@ -0,0 +1,66 @@
+use crate::model::Model;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Serialize a model as its CatBoost binary (cbm) representation. The prediction and evaluator
+/// types are not part of it, so deserialized models return raw values and evaluate on the CPU.
+/// Only models loaded with `Model::load_buffer_retained` or deserialized can be serialized:
+/// models from `Model::load` and `Model::load_buffer` do not keep their serialized form, and
+/// serializing them returns an error.
+impl Serialize for Model {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_bytes().map_err(ser::Error::custom)?)
+    }
+}
+
+impl<'de> Deserialize<'de> for Model {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(ModelVisitor)
+    }
+}
+
+struct ModelVisitor;
+
+impl<'de> Visitor<'de> for ModelVisitor {
+    type Value = Model;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a CatBoost model in binary format")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Model, E> {
+        self.visit_byte_buf(v.to_vec())
+    }
+
+    // Deserialized models keep their bytes so they can be serialized again
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Model, E> {
+        Model::load_buffer_retained(v).map_err(E::custom)
+    }
+
+    // Formats without a native byte type, such as JSON, encode bytes as a sequence of integers
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Model, A::Error> {
+        let mut buffer = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            buffer.push(byte);
+        }
+        self.visit_byte_buf(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_model() {
+        let model = Model::load_buffer_retained(std::fs::read("files/model.bin").unwrap()).unwrap();
+        let serialized = serde_json::to_vec(&model).unwrap();
+        let deserialized: Model = serde_json::from_slice(&serialized).unwrap();
+
+        assert_eq!(deserialized.to_bytes().unwrap(), model.to_bytes().unwrap());
+        assert!(serde_json::to_vec(&Model::load("files/model.bin").unwrap()).is_err());
+        assert!(serde_json::from_str::<Model>("[1, 2, 3]").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::CatBoostResult;
+use crate::model::Model;
+use crate::record::ScoreRecord;
+
+/// Number of documents scored per native call when streaming predictions
+const STREAM_BATCH_SIZE: usize = 1024;
+
+impl Model {
+    /// Lazily calculate raw model predictions on a stream of records, scoring them in batches of
+    /// up to 1024 documents so memory use stays constant however many records there are. Each
+    /// document yields as many values as the prediction type gives, see `PredictionType`.
+    /// Iteration stops after the first error, whose document indices are relative to the batch
+    /// that failed.
+    pub fn predict_iter<I>(&self, rows: I) -> PredictionIter<'_, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: ScoreRecord,
+    {
+        PredictionIter {
+            model: self,
+            rows: rows.into_iter(),
+            predictions: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+}
+
+/// Iterator over the predictions of a record stream, created by `Model::predict_iter`
+#[derive(Debug)]
+pub struct PredictionIter<'a, I> {
+    model: &'a Model,
+    rows: I,
+    predictions: std::vec::IntoIter<f64>,
+    done: bool,
+}
+
+impl<I> Iterator for PredictionIter<'_, I>
+where
+    I: Iterator,
+    I::Item: ScoreRecord,
+{
+    type Item = CatBoostResult<f64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(prediction) = self.predictions.next() {
+            return Some(Ok(prediction));
+        }
+        if self.done {
+            return None;
+        }
+
+        let batch = self
+            .rows
+            .by_ref()
+            .take(STREAM_BATCH_SIZE)
+            .collect::<Vec<_>>();
+        if batch.len() < STREAM_BATCH_SIZE {
+            self.done = true;
+        }
+        match self.model.predict_records(&batch) {
+            Ok(predictions) => {
+                self.predictions = predictions.into_iter();
+                self.predictions.next().map(Ok)
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predict_iter() {
+        let model = Model::load("files/model.bin").unwrap();
+        let rows = (0..2500).map(|i| {
+            if i.is_multiple_of(2) {
+                (vec![-10.0, 5.0, 753.0], vec![String::from("north")])
+            } else {
+                (vec![30.0, 1.0, 760.0], vec![String::from("south")])
+            }
+        });
+
+        let predictions = model
+            .predict_iter(rows)
+            .collect::<CatBoostResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(predictions.len(), 2500);
+        assert_eq!(predictions[0], 0.9980003729960197);
+        assert_eq!(predictions[2499], 0.00249414628534181);
+
+        let rows = vec![(vec![-10.0, 5.0], vec![String::from("north")])];
+        let mut predictions = model.predict_iter(rows);
+        assert!(predictions.next().unwrap().is_err());
+        assert!(predictions.next().is_none());
+    }
+}